@@ -0,0 +1,101 @@
+//! Regenerates data/english_quadgrams.txt from plain text books; see data/README.md for
+//! the sources and the exact command.
+//!
+//! Project Gutenberg books can be given as they are downloaded; the licence header and
+//! footer around the `*** START OF` and `*** END OF` markers are skipped.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: count_quadgrams <FILE|DIR>...
+
+Counts letter quadgrams across word boundaries in every .txt file given, or
+found in the given directories, and prints `ABCD count` lines, most frequent first.";
+
+/// `.txt` files under `path`, or `path` itself if it is a file.
+fn text_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if path.is_dir() {
+        let mut entries: Vec<PathBuf> =
+            fs::read_dir(path)?.map(|entry| entry.map(|e| e.path())).collect::<io::Result<_>>()?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() || entry.extension().is_some_and(|ext| ext == "txt") {
+                text_files(&entry, files)?;
+            }
+        }
+    } else {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+/// The body of a Project Gutenberg book, or the whole text if it has no markers.
+fn strip_gutenberg(text: &str) -> &str {
+    let start = text
+        .find("*** START OF")
+        .and_then(|i| text[i..].find('\n').map(|end| i + end + 1))
+        .unwrap_or(0);
+    let end = text[start..].find("*** END OF").map_or(text.len(), |i| start + i);
+    &text[start..end]
+}
+
+fn main() -> ExitCode {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() || paths.iter().any(|arg| arg == "-h" || arg == "--help") {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    }
+
+    let mut files = Vec::new();
+    for path in &paths {
+        if let Err(e) = text_files(Path::new(path), &mut files) {
+            eprintln!("error: could not read {}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let mut counts: HashMap<[u8; 4], u64> = HashMap::new();
+    let mut letters_seen = 0;
+    for file in &files {
+        let text = match fs::read_to_string(file) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("error: could not read {}: {}", file.display(), e);
+                return ExitCode::FAILURE;
+            }
+        };
+        // Each file is counted separately so no quadgram spans the join between two texts
+        let letters: Vec<u8> = strip_gutenberg(&text)
+            .bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .map(|b| b.to_ascii_uppercase())
+            .collect();
+        letters_seen += letters.len();
+        for window in letters.windows(4) {
+            *counts.entry([window[0], window[1], window[2], window[3]]).or_default() += 1;
+        }
+    }
+
+    let mut counts: Vec<([u8; 4], u64)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    for (quadgram, count) in &counts {
+        if writeln!(out, "{} {}", String::from_utf8_lossy(quadgram), count).is_err() {
+            return ExitCode::FAILURE;
+        }
+    }
+    if out.flush().is_err() {
+        return ExitCode::FAILURE;
+    }
+    eprintln!(
+        "{} letters in {} files, {} distinct quadgrams",
+        letters_seen,
+        files.len(),
+        counts.len()
+    );
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,423 @@
+use crate::scoring::{chi_squared, index_of_coincidence, indices_to_text, letter_counts, letter_indices, ENGLISH_IC};
+use crate::{compute_factors, Decrypter};
+
+use std::collections::HashMap;
+
+// Index of coincidence of uniformly random letters
+pub const RANDOM_IC: f32 = 1.0 / 26.0;
+
+// Largest period checked when looking for the maximum periodic IC
+pub const IDENTIFY_MAX_PERIOD: usize = 15;
+
+// Fewest letters a column needs for its IC to say anything about the period
+pub const MIN_COLUMN_LETTERS: usize = 14;
+
+// Index of coincidence of the non-overlapping letter pairs and triples of English text
+pub const ENGLISH_DIGRAPH_IC: f32 = 0.0075;
+pub const ENGLISH_TRIGRAPH_IC: f32 = 0.0014;
+
+/// How likely a period is for a polyalphabetic cipher, from three independent tests.
+#[derive(Debug, Clone)]
+pub struct PeriodEstimate {
+    pub period: usize,
+    /// Combined evidence, higher is more likely
+    pub score: f32,
+    /// Mean index of coincidence of the columns at this period
+    pub average_ic: f32,
+    /// Fraction of Kasiski spacings that are multiples of this period
+    pub kasiski: f32,
+}
+
+/// A sequence of three or more letters found more than once in the ciphertext.
+#[derive(Debug, Clone)]
+pub struct RepeatedSequence {
+    pub sequence: String,
+    /// Start of every occurrence, counted in letters
+    pub positions: Vec<usize>,
+    /// Distances between consecutive occurrences
+    pub spacings: Vec<usize>,
+}
+
+/// Result of a Kasiski examination.
+#[derive(Debug, Clone)]
+pub struct KasiskiReport {
+    /// Longest repeats first
+    pub repeats: Vec<RepeatedSequence>,
+    /// How many spacings each factor divides, most common first
+    pub factor_counts: Vec<(usize, usize)>,
+}
+
+/// Broad groups of classical ciphers that can be told apart from ciphertext statistics.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CipherFamily {
+    Transposition,
+    Monoalphabetic,
+    Polyalphabetic,
+    Digraphic,
+}
+
+impl CipherFamily {
+    pub fn name(self) -> &'static str {
+        match self {
+            CipherFamily::Transposition => "Transposition",
+            CipherFamily::Monoalphabetic => "Monoalphabetic Substitution",
+            CipherFamily::Polyalphabetic => "Polyalphabetic Substitution",
+            CipherFamily::Digraphic => "Digraphic Substitution",
+        }
+    }
+
+    /// Well-known ciphers in the family.
+    pub fn examples(self) -> &'static str {
+        match self {
+            CipherFamily::Transposition => "Columnar, Double columnar, Periodic, Rail fence",
+            CipherFamily::Monoalphabetic => "Caesar, Affine, simple substitution",
+            CipherFamily::Polyalphabetic => "Vigenère, Beaufort, Autokey",
+            CipherFamily::Digraphic => "Playfair, Hill",
+        }
+    }
+}
+
+/// Ciphertext statistics used to identify the cipher family.
+#[derive(Debug, Clone)]
+pub struct TextStats {
+    pub length: usize,
+    /// Number of distinct letters used
+    pub alphabet_size: usize,
+    pub ic: f32,
+    /// Monogram chi-squared against English, divided by the length
+    pub chi_squared: f32,
+    /// Period from 2 to `IDENTIFY_MAX_PERIOD` whose multiples have the most English-like
+    /// columns compared with the other periods, with their average column IC
+    pub max_periodic_ic: (usize, f32),
+    /// IC of the letters in even and odd positions
+    pub even_odd_ic: (f32, f32),
+    /// Adjacent pairs of the same letter anywhere in the text
+    pub doubled_letters: usize,
+    /// Doubled letters that fall inside a digraph, i.e. starting at an even position
+    pub doubled_digraphs: usize,
+    /// IC of the letter pairs at even positions, the blocks a Playfair or 2×2 Hill enciphers
+    pub digraph_ic: f32,
+    /// IC of the letter triples at multiples of three, the blocks of a 3×3 Hill
+    pub trigraph_ic: f32,
+    pub length_factors: Vec<usize>,
+}
+
+impl std::fmt::Display for TextStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Length: {} (factors {:?})", self.length, self.length_factors)?;
+        writeln!(f, "Alphabet size: {}", self.alphabet_size)?;
+        writeln!(f, "IC: {:.4} (English {:.4}, random {:.4})", self.ic, ENGLISH_IC, RANDOM_IC)?;
+        writeln!(f, "Chi-squared per letter: {:.3}", self.chi_squared)?;
+        writeln!(f, "Max periodic IC: {:.4} at period {}", self.max_periodic_ic.1, self.max_periodic_ic.0)?;
+        writeln!(f, "Even/odd IC: {:.4} / {:.4}", self.even_odd_ic.0, self.even_odd_ic.1)?;
+        writeln!(f, "Doubled letters: {} ({} within digraphs)", self.doubled_letters, self.doubled_digraphs)?;
+        writeln!(f, "Digraph IC: {:.4} (English {:.4}, random {:.4})",
+            self.digraph_ic, ENGLISH_DIGRAPH_IC, RANDOM_IC.powi(2))?;
+        write!(f, "Trigraph IC: {:.5} (English {:.5}, random {:.5})",
+            self.trigraph_ic, ENGLISH_TRIGRAPH_IC, RANDOM_IC.powi(3))
+    }
+}
+
+/// Statistics of a ciphertext and the cipher families ranked by confidence.
+#[derive(Debug, Clone)]
+pub struct Identification {
+    pub stats: TextStats,
+    /// Confidences sum to 1, most likely family first
+    pub families: Vec<(CipherFamily, f32)>,
+}
+
+/// Letters of the text, lowercased, with everything else removed.
+fn normalise(text: &str) -> String {
+    indices_to_text(&letter_indices(text))
+}
+
+/// Distances between consecutive occurrences of every repeated sequence in the Kasiski
+/// examination. They are counted in letters, like the key position of the Vigenère and
+/// Beaufort ciphers, so punctuation left in the text does not shift them.
+pub fn kasiski_spacings(text: &str) -> Vec<usize> {
+    kasiski_examination(text, 0).repeats.into_iter().flat_map(|repeat| repeat.spacings).collect()
+}
+
+/// Finds every repeated sequence of three or more letters, leaving out sequences that only
+/// ever occur inside a longer repeat, and tallies the factors (2 to `max_factor`) of their
+/// spacings. Positions are counted in the letters of `text`, the same stream `split_columns`
+/// splits for the attacks, and sequences run on across spaces and punctuation.
+pub fn kasiski_examination(text: &str, max_factor: usize) -> KasiskiReport {
+    let chars: Vec<char> = normalise(text).chars().collect();
+    let mut repeats = Vec::new();
+
+    // Occurrences of each repeated trigram; every longer repeat is found by splitting the
+    // occurrences of its prefix on the letter that follows, so no substring is rehashed
+    let mut trigrams: HashMap<&[char], Vec<usize>> = HashMap::new();
+    for (i, trigram) in chars.windows(3).enumerate() {
+        trigrams.entry(trigram).or_default().push(i);
+    }
+    let mut groups: Vec<Vec<usize>> = trigrams.into_values().filter(|positions| positions.len() >= 2).collect();
+
+    let mut length = 3;
+    while !groups.is_empty() {
+        let mut longer = Vec::new();
+        for positions in groups {
+            let mut by_next: HashMap<char, Vec<usize>> = HashMap::new();
+            for &p in &positions {
+                if let Some(&next) = chars.get(p + length) {
+                    by_next.entry(next).or_default().push(p);
+                }
+            }
+
+            // A repeat that extends the same way at every occurrence is part of a longer one
+            let extends_right = by_next.len() == 1 && by_next.values().all(|next| next.len() == positions.len());
+            let previous = |p: usize| p.checked_sub(1).map(|p| chars[p]);
+            let extends_left = previous(positions[0]).is_some()
+                && positions.iter().all(|&p| previous(p) == previous(positions[0]));
+            longer.extend(by_next.into_values().filter(|next| next.len() >= 2));
+            if extends_right || extends_left {
+                continue;
+            }
+
+            let sequence = chars[positions[0]..positions[0] + length].iter().collect();
+            let spacings = positions.windows(2).map(|pair| pair[1] - pair[0]).collect();
+            repeats.push(RepeatedSequence { sequence, positions, spacings });
+        }
+
+        groups = longer;
+        length += 1;
+    }
+
+    repeats.sort_by(|a, b| b.sequence.len().cmp(&a.sequence.len())
+        .then(b.positions.len().cmp(&a.positions.len()))
+        .then(a.positions[0].cmp(&b.positions[0])));
+
+    let mut tally: HashMap<usize, usize> = HashMap::new();
+    for &spacing in repeats.iter().flat_map(|repeat| &repeat.spacings) {
+        for factor in compute_factors(spacing) {
+            if (2..=max_factor).contains(&factor) {
+                *tally.entry(factor).or_default() += 1;
+            }
+        }
+    }
+    let mut factor_counts: Vec<(usize, usize)> = tally.into_iter().collect();
+    factor_counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    KasiskiReport { repeats, factor_counts }
+}
+
+/// Index of coincidence of the consecutive, non-overlapping blocks of `size` letters.
+fn block_ic(letters: &[u8], size: usize) -> f32 {
+    let mut counts: HashMap<&[u8], usize> = HashMap::new();
+    for block in letters.chunks_exact(size) {
+        *counts.entry(block).or_default() += 1;
+    }
+    let n = letters.len() / size;
+    if n < 2 {
+        return 0.0;
+    }
+    let pairs: usize = counts.values().map(|&count| count * (count - 1)).sum();
+    pairs as f32 / (n * (n - 1)) as f32
+}
+
+/// Friedman's estimate of the key length from the index of coincidence of the whole text.
+pub fn friedman_estimate(text: &str) -> f32 {
+    let ic = index_of_coincidence(&letter_counts(text));
+    if ic <= RANDOM_IC {
+        return f32::INFINITY;
+    }
+    (ENGLISH_IC - RANDOM_IC) / (ic - RANDOM_IC)
+}
+
+/// Computes the statistics used by `identify_cipher`.
+pub fn text_stats(text: &str) -> TextStats {
+    let letters = normalise(text);
+    let bytes = letters.as_bytes();
+    let counts = letter_counts(&letters);
+    let length = letters.len();
+
+    // A period is judged by how far the columns of all its multiples stand above those of
+    // the other periods, since every multiple of the true period has English-like columns
+    // and every other period random ones. Judging it by one column set alone lets periods
+    // that look uneven by chance win, so each needs a second multiple that leaves every
+    // column enough letters.
+    let max_columns = (2 * IDENTIFY_MAX_PERIOD).min(length / MIN_COLUMN_LETTERS).max(2);
+    let average_ic: Vec<f32> = (1..=max_columns)
+        .map(|period| {
+            let output = Decrypter::index_of_coincidence(text, period);
+            output.iter().sum::<f32>() / output.len() as f32
+        })
+        .collect();
+    let mean = |ics: Vec<f32>| ics.iter().sum::<f32>() / ics.len() as f32;
+    let max_periodic_ic = (2..=(max_columns / 2).max(2))
+        .map(|period| {
+            let (multiples, others): (Vec<usize>, Vec<usize>) = (1..=max_columns).partition(|p| p.is_multiple_of(period));
+            let multiples = mean(multiples.iter().map(|p| average_ic[p - 1]).collect());
+            let others = mean(others.iter().map(|p| average_ic[p - 1]).collect());
+            (period, multiples, multiples - others)
+        })
+        .max_by(|a, b| a.2.total_cmp(&b.2))
+        .map_or((1, 0.0), |(period, ic, _)| (period, ic));
+
+    let even: String = letters.chars().step_by(2).collect();
+    let odd: String = letters.chars().skip(1).step_by(2).collect();
+
+    TextStats {
+        length,
+        alphabet_size: counts.iter().filter(|&&count| count > 0).count(),
+        ic: index_of_coincidence(&counts),
+        chi_squared: if length == 0 { 0.0 } else { chi_squared(&counts) / length as f32 },
+        max_periodic_ic,
+        even_odd_ic: (index_of_coincidence(&letter_counts(&even)), index_of_coincidence(&letter_counts(&odd))),
+        doubled_letters: bytes.windows(2).filter(|pair| pair[0] == pair[1]).count(),
+        doubled_digraphs: bytes.chunks_exact(2).filter(|pair| pair[0] == pair[1]).count(),
+        digraph_ic: block_ic(bytes, 2),
+        trigraph_ic: block_ic(bytes, 3),
+        length_factors: compute_factors(length),
+    }
+}
+
+/// Ranks the cipher families by how well the ciphertext statistics fit each of them.
+pub fn identify_cipher(text: &str) -> Identification {
+    let stats = text_stats(text);
+
+    // 1 when the letters are as uneven as English, 0 when they look random
+    let englishness = |ic: f32| ((ic - RANDOM_IC) / (ENGLISH_IC - RANDOM_IC)).clamp(0.0, 1.0);
+    let ic = englishness(stats.ic);
+    // Transpositions and monoalphabetic substitutions keep the English IC, so neither
+    // scores unless the IC is close to it
+    let english = ((ic - 0.6) / 0.2).clamp(0.0, 1.0);
+    // How much of the English IC the columns at the best period recover. Digraphic ciphers
+    // lift the columns of the positions within their blocks a little too, so only a clear
+    // rise counts.
+    let recovered = (stats.max_periodic_ic.1 - stats.ic) / (ENGLISH_IC - RANDOM_IC);
+    let periodic = ((recovered - 0.3) / 0.4).clamp(0.0, 1.0);
+
+    // Close to 1 when the letter frequencies themselves match English
+    let frequency_fit = 1.0 / (1.0 + stats.chi_squared);
+
+    // Digraphic ciphers flatten the letter frequencies but keep those of the blocks they
+    // encipher, which stay as uneven as English pairs or triples. A Vigenère key of two or
+    // three letters does too, but leaves every column at that period English-like.
+    let blocks = |block_ic: f32, random: f32, english: f32, size: usize| {
+        let columns = Decrypter::index_of_coincidence(text, size);
+        let column_ic = englishness(columns.iter().sum::<f32>() / size as f32);
+        ((block_ic - random) / (english - random)).clamp(0.0, 1.0)
+            * (1.0 - ic)
+            * (1.0 - ((column_ic - 0.6) / 0.3).clamp(0.0, 1.0))
+    };
+    let pairs = blocks(stats.digraph_ic, RANDOM_IC.powi(2), ENGLISH_DIGRAPH_IC, 2);
+    let triples = blocks(stats.trigraph_ic, RANDOM_IC.powi(3), ENGLISH_TRIGRAPH_IC, 3);
+
+    // Playfair needs an even length, never enciphers a doubled letter within a pair and
+    // drops J, so its ciphertext uses at most 25 letters
+    let mut playfair = pairs;
+    if !stats.length.is_multiple_of(2) {
+        playfair *= 0.1;
+    }
+    if stats.doubled_digraphs > 0 {
+        playfair *= 0.2;
+    }
+    if stats.alphabet_size == 26 {
+        playfair *= 0.5;
+    }
+
+    // Hill only needs the length to be a multiple of the block size
+    let hill_pairs = if stats.length.is_multiple_of(2) { pairs } else { pairs * 0.1 };
+    let hill_triples = if stats.length.is_multiple_of(3) { triples } else { triples * 0.1 };
+    let hill = hill_pairs.max(hill_triples);
+
+    // Digraphic ciphers leave no period, while a periodic key explains any English-like
+    // blocks. An Autokey has neither, so a flat text with random blocks still leans
+    // polyalphabetic.
+    let digraphic = playfair.max(hill) * (1.0 - periodic);
+    let polyalphabetic = periodic + (1.0 - periodic) * 0.3 * (1.0 - pairs.max(triples));
+
+    let raw = [
+        (CipherFamily::Transposition, english * frequency_fit),
+        (CipherFamily::Monoalphabetic, english * (1.0 - frequency_fit)),
+        (CipherFamily::Polyalphabetic, (1.0 - english) * polyalphabetic),
+        (CipherFamily::Digraphic, digraphic),
+    ];
+
+    let total: f32 = raw.iter().map(|&(_, score)| score).sum();
+    let mut families: Vec<(CipherFamily, f32)> = raw.iter()
+        .map(|&(family, score)| (family, if total > 0.0 { score / total } else { 0.25 }))
+        .collect();
+    families.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    Identification { stats, families }
+}
+
+/// Ranks periods `1..=max_period`, most likely first, by combining the per-column IC
+/// averages, Kasiski spacings and the Friedman estimate. Columns are taken over the letters
+/// of `text`, the same way `split_columns` splits it for the attacks.
+pub fn rank_periods(text: &str, max_period: usize) -> Vec<PeriodEstimate> {
+    let spacings = kasiski_spacings(text);
+    let friedman = friedman_estimate(text);
+
+    let mut estimates: Vec<PeriodEstimate> = (1..=max_period.max(1)).map(|period| {
+        let output = Decrypter::index_of_coincidence(text, period);
+        let average_ic = output.iter().sum::<f32>() / output.len() as f32;
+
+        // 1 when the columns look like English, 0 when they look random
+        let ic_score = ((average_ic - RANDOM_IC) / (ENGLISH_IC - RANDOM_IC)).clamp(0.0, 1.0);
+
+        let kasiski = if spacings.is_empty() || period == 1 {
+            0.0
+        } else {
+            spacings.iter().filter(|&&s| s.is_multiple_of(period)).count() as f32 / spacings.len() as f32
+        };
+
+        let friedman_score = 1.0 / (1.0 + (period as f32 - friedman).abs());
+
+        // Multiples of the true period score as well as the period itself on IC and
+        // Kasiski, so longer periods are penalised slightly to prefer the smallest
+        let score = 0.5 * ic_score + 0.3 * kasiski + 0.2 * friedman_score - 0.01 * period as f32;
+
+        PeriodEstimate { period, score, average_ic, kasiski }
+    }).collect();
+
+    estimates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    estimates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::{Autokey, Beaufort, Columnar, Hill, Playfair, Substitution, Vigenere, SAMPLE_TEXT};
+    use crate::{parse_shift_key, AnyCipher, AttackParams, Cipher};
+
+    #[test]
+    fn kasiski_counts_letters_like_the_period_ranking() {
+        let ciphertext = Vigenere.encrypt(&parse_shift_key("lemon").unwrap(), SAMPLE_TEXT);
+
+        let report = kasiski_examination(&ciphertext, 10);
+        let chars: Vec<char> = normalise(&ciphertext).chars().collect();
+        for repeat in &report.repeats {
+            for &p in &repeat.positions {
+                assert_eq!(chars[p..p + repeat.sequence.len()].iter().collect::<String>(), repeat.sequence);
+            }
+        }
+        assert_eq!(report.factor_counts[0].0, 5);
+        assert_eq!(rank_periods(&ciphertext, 10)[0].period, 5);
+    }
+
+    fn family(cipher: &dyn AnyCipher, key: &str, length: usize) -> CipherFamily {
+        let plaintext: String = normalise(SAMPLE_TEXT).chars().cycle().take(length).collect();
+        let ciphertext = cipher.encrypt_with(key, &plaintext, &AttackParams::default()).unwrap();
+        identify_cipher(&ciphertext).families[0].0
+    }
+
+    #[test]
+    fn identifies_polyalphabetic_ciphers() {
+        assert_eq!(family(&Vigenere, "lemon", 200), CipherFamily::Polyalphabetic);
+        assert_eq!(family(&Beaufort, "lemon", 200), CipherFamily::Polyalphabetic);
+        assert_eq!(family(&Autokey, "queen", 613), CipherFamily::Polyalphabetic);
+        assert_eq!(identify_cipher(&Vigenere.encrypt(&parse_shift_key("lemon").unwrap(), SAMPLE_TEXT)).stats.max_periodic_ic.0, 5);
+    }
+
+    #[test]
+    fn identifies_the_other_families() {
+        assert_eq!(family(&Columnar, "3,1,4,0,2,5", 200), CipherFamily::Transposition);
+        assert_eq!(family(&Substitution, "qwertyuiopasdfghjklzxcvbnm", 200), CipherFamily::Monoalphabetic);
+        assert_eq!(family(&Playfair, "monarchy", 400), CipherFamily::Digraphic);
+        assert_eq!(family(&Hill, "3 3 2 5", 400), CipherFamily::Digraphic);
+    }
+}
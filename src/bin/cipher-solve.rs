@@ -0,0 +1,248 @@
+use cipher_solver_comp3260::{scorer_from_id, scorers, Candidate, CipherType, Decrypter, CIPHERS};
+use cipher_solver_comp3260::analysis::identify_cipher;
+use cipher_solver_comp3260::ciphers::ColumnarSearch;
+use indicatif::{ProgressBar, ProgressStyle};
+
+use std::io::Read;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+const USAGE: &str = "\
+Usage: cipher-solve [OPTIONS] <FILE>...
+
+Solves each ciphertext file and prints the ranked candidates, or encrypts/decrypts
+each file with a known key. Use `-` as a file name to read from stdin. Transposition
+keys can be a column order such as 2,0,1 or a keyword such as ZEBRAS.
+
+Options:
+  -c, --cipher <TYPE>        cipher to attack, e.g. columnar, vigenere or caesar [default: columnar]
+  -p, --period <N>           period for periodic, Vigenère and Beaufort [default: 3]
+  -k, --max-key-length <N>   max key length (columnar), max period (periodic), max rails or max primer (autokey) [default: 8]
+  -a, --all-periods          periodic: check every period from --period up to --max-key-length
+  -A, --auto-period          vigenere/beaufort: detect the period, trying periods up to --max-key-length
+  -b, --beam <N>             vigenere: beam width over runner-up shifts per key letter [default: off]
+  -r, --restarts <N>         hill-climbing attacks: number of random restarts, or of chains climbed by adjacency [default: 20]
+      --crib <TEXT>          hill: known plaintext for a known-plaintext attack
+  -D, --dictionary           vigenere/beaufort: try every dictionary word of any length as the key, then pairs of words up to --max-key-length letters, at most 2000000 pairs
+  -w, --wordlist <FILE>      extra words for --dictionary, whitespace separated, paired before the bundled words (implies --dictionary)
+  -S, --search <NAME>        columnar: exhaustive, climb or adjacency (not with --transpose) [default: exhaustive]
+  -t, --transpose            columnar/double-columnar: read the grid transposed
+  -s, --scorer <NAME>        heuristic, chi2, quadgram, words or ic [default: heuristic]
+  -e, --encrypt <KEY>        encrypt each file with KEY instead of solving
+  -d, --decrypt <KEY>        decrypt each file with KEY instead of solving
+  -i, --identify             print ciphertext statistics and likely cipher families instead of solving
+  -n, --top <N>              number of candidates to print per file [default: all]
+  -q, --quiet                do not show a progress bar
+  -v, --verbose              print progress messages from the attacks to stderr
+  -h, --help                 print this help";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Encrypt,
+    Decrypt,
+}
+
+struct Args {
+    decrypter: Decrypter,
+    mode: Option<Mode>,
+    identify: bool,
+    top: Option<usize>,
+    quiet: bool,
+    files: Vec<String>,
+}
+
+fn parse_cipher_type(name: &str) -> Result<CipherType, String> {
+    CipherType::from_id(&name.to_lowercase()).ok_or_else(|| {
+        let known: Vec<&str> = CIPHERS.iter().map(|cipher| cipher.id()).collect();
+        format!("unknown cipher type `{}` (expected one of: {})", name, known.join(", "))
+    })
+}
+
+fn parse_number(flag: &str, value: Option<String>) -> Result<usize, String> {
+    let value = value.ok_or_else(|| format!("missing value for `{}`", flag))?;
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("invalid value `{}` for `{}`", value, flag)),
+    }
+}
+
+fn parse_args() -> Result<Option<Args>, String> {
+    let mut args = Args {
+        decrypter: Decrypter::default(),
+        mode: None,
+        identify: false,
+        top: None,
+        quiet: false,
+        files: Vec::new(),
+    };
+    let params = &mut args.decrypter.params;
+
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-c" | "--cipher" => {
+                let name = argv.next().ok_or("missing value for `--cipher`")?;
+                args.decrypter.cipher_type = parse_cipher_type(&name)?;
+            }
+            "-p" | "--period" => params.period = parse_number(&arg, argv.next())?,
+            "-k" | "--max-key-length" => params.max_key_length = parse_number(&arg, argv.next())?,
+            "-a" | "--all-periods" => params.check_all_periods = true,
+            "-A" | "--auto-period" => params.auto_period = true,
+            "-b" | "--beam" => params.beam_width = parse_number(&arg, argv.next())?,
+            "-r" | "--restarts" => params.restarts = parse_number(&arg, argv.next())?,
+            "--crib" => params.crib = argv.next().ok_or("missing value for `--crib`")?,
+            "-S" | "--search" => {
+                let name = argv.next().ok_or("missing value for `--search`")?;
+                params.columnar_search = ColumnarSearch::from_id(&name.to_lowercase()).ok_or_else(|| {
+                    let known: Vec<&str> = ColumnarSearch::ALL.iter().map(|search| search.id()).collect();
+                    format!("unknown search `{}` (expected one of: {})", name, known.join(", "))
+                })?;
+            }
+            "-D" | "--dictionary" => params.dictionary_attack = true,
+            "-w" | "--wordlist" => {
+                let path = argv.next().ok_or("missing value for `--wordlist`")?;
+                let contents = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("could not read wordlist {}: {}", path, e))?;
+                Arc::make_mut(&mut params.wordlist).extend(contents.split_whitespace().map(str::to_owned));
+                params.dictionary_attack = true;
+            }
+            "-t" | "--transpose" => params.transpose = true,
+            "-s" | "--scorer" => {
+                let name = argv.next().ok_or("missing value for `--scorer`")?;
+                params.scorer = scorer_from_id(&name.to_lowercase()).ok_or_else(|| {
+                    let known: Vec<&str> = scorers().iter().map(|scorer| scorer.id()).collect();
+                    format!("unknown scorer `{}` (expected one of: {})", name, known.join(", "))
+                })?;
+            }
+            "-e" | "--encrypt" | "-d" | "--decrypt" => {
+                let key = argv.next().ok_or_else(|| format!("missing key for `{}`", arg))?;
+                let mode = if arg == "-e" || arg == "--encrypt" { Mode::Encrypt } else { Mode::Decrypt };
+                args.mode = Some(mode);
+                args.decrypter.key = Some(key);
+            }
+            "-i" | "--identify" => args.identify = true,
+            "-n" | "--top" => args.top = Some(parse_number(&arg, argv.next())?),
+            "-q" | "--quiet" => args.quiet = true,
+            "-v" | "--verbose" => params.verbose = true,
+            "-" => args.files.push(arg),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => args.files.push(arg),
+        }
+    }
+
+    if params.transpose && !params.columnar_search.supports_transpose() {
+        return Err(format!("`--search {}` cannot be used with `--transpose`", params.columnar_search.id()));
+    }
+    if args.files.is_empty() {
+        return Err("no input files given".to_owned());
+    }
+    Ok(Some(args))
+}
+
+fn print_identification(path: &str, text: &str) {
+    let identification = identify_cipher(text);
+    println!("== {} ==", path);
+    println!("{}", identification.stats);
+    println!();
+    for (family, confidence) in identification.families {
+        println!("{:>3.0}%  {} ({})", confidence * 100.0, family.name(), family.examples());
+    }
+    println!();
+}
+
+/// The file's text as it is, apart from the line break that ends it.
+fn read_text(path: &str) -> std::io::Result<String> {
+    let mut contents = if path == "-" {
+        let mut buffer = String::new();
+        std::io::stdin().read_to_string(&mut buffer)?;
+        buffer
+    } else {
+        std::fs::read_to_string(path)?
+    };
+
+    for ending in ["\n", "\r"] {
+        if contents.ends_with(ending) {
+            contents.pop();
+        }
+    }
+    Ok(contents)
+}
+
+/// Same preprocessing as the GUI's "Open File" button, for the text to attack.
+fn without_whitespace(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+fn print_candidates(path: &str, candidates: &[Candidate], top: Option<usize>) {
+    println!("== {} ==", path);
+    for candidate in candidates.iter().take(top.unwrap_or(candidates.len())) {
+        println!("{} (Score: {:.3})", candidate.name, candidate.score);
+        println!("{}", candidate.text);
+        println!();
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let decrypter = &args.decrypter;
+
+    let progress = if args.quiet {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(args.files.len() as u64)
+    };
+    progress.set_style(
+        ProgressStyle::with_template("{spinner} [{elapsed_precise}] {bar:30} {pos}/{len} {msg}")
+            .unwrap(),
+    );
+    progress.enable_steady_tick(std::time::Duration::from_millis(100));
+
+    let mut failed = false;
+    for path in &args.files {
+        progress.set_message(path.clone());
+        // Encrypting or decrypting with a key keeps the layout, which attacks have no use for
+        match read_text(path) {
+            Ok(text) if args.identify => progress.suspend(|| print_identification(path, &without_whitespace(&text))),
+            Ok(text) => match args.mode {
+                Some(mode) => {
+                    let result = if mode == Mode::Encrypt {
+                        decrypter.encrypt(&text)
+                    } else {
+                        decrypter.decrypt_with_key(&text)
+                    };
+                    match result {
+                        Ok(output) => progress.suspend(|| println!("{}", output)),
+                        Err(e) => {
+                            progress.suspend(|| eprintln!("error: invalid key: {}", e));
+                            failed = true;
+                        }
+                    }
+                }
+                None => {
+                    let candidates = decrypter.decrypt(&without_whitespace(&text));
+                    progress.suspend(|| print_candidates(path, &candidates, args.top));
+                }
+            },
+            Err(e) => {
+                progress.suspend(|| eprintln!("error: could not read {}: {}", path, e));
+                failed = true;
+            }
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
@@ -0,0 +1,434 @@
+use crate::ciphers::{
+    Affine, Amsco, Atbash, Autokey, Beaufort, Caesar, Columnar, ColumnarSearch, DoubleColumnar,
+    Hill, Myszkowski, Periodic, Playfair, RailFence, Redefence, RouteCipher, Substitution, Vigenere,
+};
+use crate::scoring::{english_words, HeuristicScorer, Scorer};
+use crate::{Candidate, CipherType};
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::sync::{Arc, Mutex};
+
+/// Search settings shared by every attack. Each cipher only reads the fields it needs.
+#[derive(Clone)]
+pub struct AttackParams {
+    pub max_key_length: usize,
+    pub period: usize,
+    pub check_all_periods: bool,
+    /// Vigenère/Beaufort: ignore `period` and attack the likeliest periods up to `max_key_length`
+    pub auto_period: bool,
+    /// Vigenère: number of keys kept by the beam search over runner-up shifts, 0 to disable
+    pub beam_width: usize,
+    /// Hill-climbing attacks: number of independent searches from random keys
+    pub restarts: usize,
+    /// Hill: known plaintext from somewhere in the message, empty for a ciphertext-only attack
+    pub crib: String,
+    /// Columnar: exhaustive search or hill climbing over column orders
+    pub columnar_search: ColumnarSearch,
+    /// Vigenère/Beaufort: try dictionary and `wordlist` words of any length as keys, and pairs
+    /// of them up to `max_key_length` letters, instead of frequency analysis
+    pub dictionary_attack: bool,
+    /// Words tried by the dictionary attack in addition to the bundled dictionary
+    pub wordlist: Arc<Vec<String>>,
+    pub transpose: bool,
+    /// Print progress messages from the attacks to stderr
+    pub verbose: bool,
+    pub scorer: Arc<dyn Scorer>,
+}
+
+impl Default for AttackParams {
+    fn default() -> Self {
+        Self {
+            max_key_length: 8,
+            period: 3,
+            check_all_periods: false,
+            auto_period: false,
+            beam_width: 0,
+            restarts: 20,
+            crib: String::new(),
+            columnar_search: ColumnarSearch::Exhaustive,
+            dictionary_attack: false,
+            wordlist: Arc::new(Vec::new()),
+            transpose: false,
+            verbose: false,
+            scorer: Arc::new(HeuristicScorer),
+        }
+    }
+}
+
+impl AttackParams {
+    /// Prints an attack's progress message to stderr when `verbose` is set.
+    pub(crate) fn log(&self, message: std::fmt::Arguments) {
+        if self.verbose {
+            eprintln!("{}", message);
+        }
+    }
+}
+
+/// A control the GUI shows for a cipher's attack, each backed by fields of [`AttackParams`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+    /// `transpose`, shown beside the cipher choice
+    Transpose,
+    /// `max_key_length`, under the given label
+    MaxKeyLength(&'static str),
+    Period,
+    CheckAllPeriods,
+    AutoPeriod,
+    BeamWidth,
+    Restarts,
+    Crib,
+    ColumnarSearch,
+    /// `dictionary_attack` and the extra `wordlist`
+    Dictionary,
+    /// A button opening the substitution workbench on the ciphertext
+    Workbench,
+}
+
+/// A classical cipher with a typed key.
+///
+/// New ciphers implement this trait and are added to [`CIPHERS`]; the GUI and CLI
+/// pick them up from there.
+pub trait Cipher: Sync {
+    type Key;
+
+    fn cipher_type(&self) -> CipherType;
+    /// Short lowercase identifier, as accepted on the command line.
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    /// Parses a key typed by the user. Options that belong to the key but are set with
+    /// their own control (such as the columnar transpose flag) are taken from `params`.
+    fn parse_key(&self, key: &str, params: &AttackParams) -> Result<Self::Key, String>;
+    fn encrypt(&self, key: &Self::Key, plaintext: &str) -> String;
+    fn decrypt(&self, key: &Self::Key, ciphertext: &str) -> String;
+    fn attack(&self, ciphertext: &str, params: &AttackParams) -> Vec<Candidate>;
+
+    /// Settings the attack reads, in the order the GUI shows them. They can depend on
+    /// the current `params`, such as restarts only for a hill-climbing search.
+    fn settings(&self, _params: &AttackParams) -> Vec<Setting> {
+        Vec::new()
+    }
+
+    /// The candidates of `attack`, each with the cipher alphabet that produced it, for
+    /// ciphers the substitution workbench can carry on from.
+    fn attack_with_alphabets(&self, _ciphertext: &str, _params: &AttackParams) -> Option<Vec<(Candidate, [u8; 26])>> {
+        None
+    }
+}
+
+/// Object-safe view of a [`Cipher`], used for the registry.
+pub trait AnyCipher: Sync {
+    fn cipher_type(&self) -> CipherType;
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn encrypt_with(&self, key: &str, plaintext: &str, params: &AttackParams) -> Result<String, String>;
+    fn decrypt_with(&self, key: &str, ciphertext: &str, params: &AttackParams) -> Result<String, String>;
+    fn attack(&self, ciphertext: &str, params: &AttackParams) -> Vec<Candidate>;
+    fn settings(&self, params: &AttackParams) -> Vec<Setting>;
+    fn attack_with_alphabets(&self, ciphertext: &str, params: &AttackParams) -> Option<Vec<(Candidate, [u8; 26])>>;
+}
+
+impl<C: Cipher> AnyCipher for C {
+    fn cipher_type(&self) -> CipherType {
+        Cipher::cipher_type(self)
+    }
+
+    fn id(&self) -> &'static str {
+        Cipher::id(self)
+    }
+
+    fn name(&self) -> &'static str {
+        Cipher::name(self)
+    }
+
+    fn encrypt_with(&self, key: &str, plaintext: &str, params: &AttackParams) -> Result<String, String> {
+        let key = self.parse_key(key, params)?;
+        Ok(self.encrypt(&key, plaintext))
+    }
+
+    fn decrypt_with(&self, key: &str, ciphertext: &str, params: &AttackParams) -> Result<String, String> {
+        let key = self.parse_key(key, params)?;
+        Ok(self.decrypt(&key, ciphertext))
+    }
+
+    fn attack(&self, ciphertext: &str, params: &AttackParams) -> Vec<Candidate> {
+        Cipher::attack(self, ciphertext, params)
+    }
+
+    fn settings(&self, params: &AttackParams) -> Vec<Setting> {
+        Cipher::settings(self, params)
+    }
+
+    fn attack_with_alphabets(&self, ciphertext: &str, params: &AttackParams) -> Option<Vec<(Candidate, [u8; 26])>> {
+        Cipher::attack_with_alphabets(self, ciphertext, params)
+    }
+}
+
+/// Parses a transposition key such as `2,0,1`, `2 0 1` or the keyword `zebras` into a
+/// permutation of `0..n`.
+pub fn parse_permutation(key: &str) -> Result<Vec<usize>, String> {
+    let key = key.trim();
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Ok(keyword_order(key));
+    }
+
+    let order = key
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<usize>().map_err(|_| format!("`{}` is not a column number", part)))
+        .collect::<Result<Vec<usize>, String>>()?;
+
+    if order.is_empty() {
+        return Err("the key is empty".to_owned());
+    }
+    let mut seen = vec![false; order.len()];
+    for &k in &order {
+        if k >= order.len() || seen[k] {
+            return Err(format!("the key must use each of 0..{} exactly once", order.len()));
+        }
+        seen[k] = true;
+    }
+    Ok(order)
+}
+
+/// Column order given by a keyword: the rank of each letter in alphabetical order, with
+/// repeated letters ranked left to right, so `zebras` gives `[5, 2, 1, 3, 0, 4]`.
+pub fn keyword_order(keyword: &str) -> Vec<usize> {
+    let letters: Vec<u8> = keyword.bytes().map(|b| b.to_ascii_lowercase()).collect();
+    let mut by_letter: Vec<usize> = (0..letters.len()).collect();
+    by_letter.sort_by_key(|&i| (letters[i], i));
+
+    let mut order = vec![0; letters.len()];
+    for (rank, i) in by_letter.into_iter().enumerate() {
+        order[i] = rank;
+    }
+    order
+}
+
+/// Replaces each value with its rank among the distinct values, so `tomato` becomes
+/// `[3, 2, 1, 0, 3, 2]`.
+pub(crate) fn dense_ranks(values: &[usize]) -> Vec<usize> {
+    let distinct: Vec<usize> = values.iter().copied().sorted().dedup().collect();
+    values.iter().map(|value| distinct.binary_search(value).unwrap()).collect()
+}
+
+/// Words from the bundled dictionary that give the column order `order`, alphabetically.
+pub fn keywords_for_order(order: &[usize]) -> Vec<&'static str> {
+    let mut words: Vec<&'static str> = english_words()
+        .filter(|word| word.len() == order.len() && keyword_order(word) == order)
+        .collect();
+    words.sort();
+    words
+}
+
+/// Words from the bundled dictionary whose letters rank as `ranks`, with repeated letters
+/// sharing a rank as in a Myszkowski key, alphabetically.
+pub fn keywords_for_ranks(ranks: &[usize]) -> Vec<&'static str> {
+    let mut words: Vec<&'static str> = english_words()
+        .filter(|word| word.len() == ranks.len() && dense_ranks(&word.bytes().map(usize::from).collect::<Vec<_>>()) == ranks)
+        .collect();
+    words.sort();
+    words
+}
+
+/// A recovered key for display, followed by up to three of the dictionary `keywords` that
+/// produce it.
+fn with_keywords(key: &[usize], keywords: Vec<&str>) -> String {
+    if keywords.is_empty() || key.len() < 3 {
+        return format!("{:?}", key);
+    }
+    format!("{:?} (keyword: {})", key, keywords.into_iter().take(3).collect::<Vec<_>>().join(", "))
+}
+
+/// A recovered column order with the keywords that give it.
+pub(crate) fn format_order(order: &[usize]) -> String {
+    with_keywords(order, keywords_for_order(order))
+}
+
+/// Recovered Myszkowski ranks with the keywords that give them.
+pub(crate) fn format_ranks(ranks: &[usize]) -> String {
+    with_keywords(ranks, keywords_for_ranks(ranks))
+}
+
+/// Parses a keyword such as `lemon` into one shift per letter, `a` = 0.
+pub fn parse_shift_key(key: &str) -> Result<Vec<usize>, String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("the key is empty".to_owned());
+    }
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphabetic() {
+                Ok((c.to_ascii_lowercase() as u8 - b'a') as usize)
+            } else {
+                Err(format!("`{}` is not a letter", c))
+            }
+        })
+        .collect()
+}
+
+/// Every supported cipher, in the order they are offered to the user.
+pub static CIPHERS: &[&dyn AnyCipher] = &[
+    &Columnar, &DoubleColumnar, &Myszkowski, &Amsco, &Periodic, &RailFence, &Redefence, &RouteCipher,
+    &Vigenere, &Beaufort, &Autokey,
+    &Caesar, &Atbash, &Affine, &Substitution,
+    &Playfair, &Hill,
+];
+
+impl CipherType {
+    pub fn cipher(self) -> &'static dyn AnyCipher {
+        *CIPHERS.iter()
+            .find(|cipher| cipher.cipher_type() == self)
+            .expect("every cipher type is registered")
+    }
+
+    pub fn name(self) -> &'static str {
+        self.cipher().name()
+    }
+
+    pub fn from_id(id: &str) -> Option<CipherType> {
+        CIPHERS.iter()
+            .find(|cipher| cipher.id() == id)
+            .map(|cipher| cipher.cipher_type())
+    }
+}
+
+/// One generator per parallel task, seeded from `rng`, so a seeded attack finds the same
+/// keys whichever order its tasks run in.
+pub(crate) fn task_rngs(rng: &mut impl Rng, tasks: usize) -> Vec<StdRng> {
+    (0..tasks).map(|_| StdRng::seed_from_u64(rng.random())).collect()
+}
+
+struct Ranked<K> {
+    score: f32,
+    text: String,
+    key: K,
+}
+
+impl<K> PartialEq for Ranked<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.score.total_cmp(&other.score) == Ordering::Equal
+    }
+}
+
+impl<K> Eq for Ranked<K> {}
+
+impl<K> PartialOrd for Ranked<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K> Ord for Ranked<K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score.total_cmp(&other.score)
+    }
+}
+
+/// Thread-safe collection of the best scoring decryptions seen so far.
+pub(crate) struct CandidateHeap<K> {
+    heap: Mutex<BinaryHeap<Reverse<Ranked<K>>>>,
+    limit: usize,
+}
+
+impl<K> CandidateHeap<K> {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            heap: Mutex::new(BinaryHeap::new()),
+            limit,
+        }
+    }
+
+    pub(crate) fn push(&self, score: f32, text: String, key: K) {
+        let mut heap_guard = self.heap.lock().unwrap();
+        heap_guard.push(Reverse(Ranked { score, text, key }));
+
+        if heap_guard.len() > self.limit {
+            heap_guard.pop();
+        }
+    }
+
+    /// Like `push`, but skips a key that is already among the best, for searches that can
+    /// reach the same key more than once.
+    pub(crate) fn push_distinct(&self, score: f32, text: String, key: K)
+    where
+        K: PartialEq,
+    {
+        let mut heap_guard = self.heap.lock().unwrap();
+        if heap_guard.iter().any(|Reverse(ranked)| ranked.key == key) {
+            return;
+        }
+        heap_guard.push(Reverse(Ranked { score, text, key }));
+
+        if heap_guard.len() > self.limit {
+            heap_guard.pop();
+        }
+    }
+
+    /// Best candidates first, with `format` turning each key and text into the displayed text.
+    pub(crate) fn into_candidates(self, format: impl Fn(&K, &str) -> String) -> Vec<Candidate> {
+        self.into_keyed_candidates(format).into_iter().map(|(candidate, _)| candidate).collect()
+    }
+
+    /// Like `into_candidates`, with the key each candidate was decrypted with.
+    pub(crate) fn into_keyed_candidates(self, format: impl Fn(&K, &str) -> String) -> Vec<(Candidate, K)> {
+        let best = self.heap.into_inner().unwrap().into_sorted_vec();
+
+        best.into_iter().enumerate().map(|(i, Reverse(ranked))| {
+            let candidate = Candidate {
+                name: format!("Candidate {}", i + 1),
+                score: ranked.score,
+                text: format(&ranked.key, &ranked.text),
+            };
+            (candidate, ranked.key)
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_ids_are_unique_and_settings_follow_params() {
+        for cipher in CIPHERS {
+            assert_eq!(CipherType::from_id(cipher.id()), Some(cipher.cipher_type()));
+            let settings = cipher.settings(&AttackParams::default());
+            assert!(settings.iter().enumerate().all(|(i, setting)| !settings[..i].contains(setting)));
+        }
+
+        let climb = AttackParams { columnar_search: ColumnarSearch::HillClimb, ..AttackParams::default() };
+        assert!(!Cipher::settings(&Columnar, &AttackParams::default()).contains(&Setting::Restarts));
+        assert!(Cipher::settings(&Columnar, &climb).contains(&Setting::Restarts));
+        let adjacency = AttackParams { columnar_search: ColumnarSearch::Adjacency, ..AttackParams::default() };
+        assert!(Cipher::settings(&Columnar, &adjacency).contains(&Setting::Restarts));
+        assert!(!Cipher::settings(&Periodic, &AttackParams::default()).contains(&Setting::Transpose));
+
+        let dictionary = AttackParams { dictionary_attack: true, ..AttackParams::default() };
+        assert!(Cipher::settings(&Vigenere, &AttackParams::default()).contains(&Setting::BeamWidth));
+        assert!(!Cipher::settings(&Vigenere, &dictionary).contains(&Setting::BeamWidth));
+        assert!(!Cipher::settings(&Beaufort, &dictionary).contains(&Setting::AutoPeriod));
+    }
+
+    #[test]
+    fn keywords_rank_repeated_letters_left_to_right() {
+        assert_eq!(keyword_order("ZEBRAS"), [5, 2, 1, 3, 0, 4]);
+        assert_eq!(keyword_order("letter"), [2, 0, 4, 5, 1, 3]);
+        assert!(keywords_for_order(&[5, 2, 1, 3, 0, 4]).contains(&"zebras"));
+        for word in keywords_for_order(&[2, 0, 4, 5, 1, 3]) {
+            assert_eq!(keyword_order(word), [2, 0, 4, 5, 1, 3]);
+        }
+    }
+
+    #[test]
+    fn tied_ranks_are_matched_by_repeated_letters() {
+        assert_eq!(dense_ranks(&[20, 15, 13, 1, 20, 15]), [3, 2, 1, 0, 3, 2]);
+        assert!(keywords_for_ranks(&[1, 0, 3, 3, 0, 2]).contains(&"letter"));
+        assert!(!keywords_for_order(&[1, 0, 3, 3, 0, 2]).contains(&"letter"));
+        assert_eq!(format_ranks(&[1, 0, 2, 0, 2, 0]), "[1, 0, 2, 0, 2, 0] (keyword: banana)");
+    }
+}
@@ -0,0 +1,159 @@
+use crate::cipher::{AttackParams, CandidateHeap, Cipher};
+use crate::{Candidate, CipherType};
+
+use super::vigenere::shift_text;
+
+pub struct Affine;
+
+/// Encryption is `c = a * p + b (mod 26)`, with `a` coprime to 26.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AffineKey {
+    a: usize,
+    b: usize,
+    /// Inverse of `a` mod 26, used to decrypt
+    a_inv: usize,
+}
+
+impl AffineKey {
+    /// Both numbers are taken mod 26; fails if `a` is not coprime to 26.
+    pub fn new(a: usize, b: usize) -> Result<Self, String> {
+        let (a, b) = (a % 26, b % 26);
+        let a_inv = mod_inverse(a, 26).ok_or_else(|| format!("a={} is not coprime to 26", a))?;
+        Ok(Self { a, b, a_inv })
+    }
+
+    pub fn a(&self) -> usize {
+        self.a
+    }
+
+    pub fn b(&self) -> usize {
+        self.b
+    }
+}
+
+impl std::fmt::Display for AffineKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a={}, b={}", self.a, self.b)
+    }
+}
+
+impl Cipher for Affine {
+    type Key = AffineKey;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Affine
+    }
+
+    fn id(&self) -> &'static str {
+        "affine"
+    }
+
+    fn name(&self) -> &'static str {
+        "Affine Cipher"
+    }
+
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<AffineKey, String> {
+        parse_affine_key(key)
+    }
+
+    fn encrypt(&self, key: &AffineKey, plaintext: &str) -> String {
+        shift_text(plaintext, &[0], |_, letter| ((key.a * letter as usize + key.b) % 26) as u8)
+    }
+
+    fn decrypt(&self, key: &AffineKey, ciphertext: &str) -> String {
+        shift_text(ciphertext, &[0], |_, letter| ((key.a_inv * (letter as usize + 26 - key.b)) % 26) as u8)
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        let heap = CandidateHeap::new(5);
+
+        // 12 values of a coprime to 26 times 26 values of b: 312 keys
+        for a in 1..26 {
+            for b in 0..26 {
+                let Ok(key) = AffineKey::new(a, b) else { continue };
+                let decrypted_text = self.decrypt(&key, text);
+                let score = params.scorer.score(&decrypted_text);
+                heap.push(score, decrypted_text, key);
+            }
+        }
+
+        heap.into_candidates(|key, text| format!("Key: {}\nDecryption:\n{}", key, text))
+    }
+}
+
+/// The inverse of `a` modulo `m`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: usize, m: usize) -> Option<usize> {
+    (1..m).find(|&x| (a % m) * x % m == 1)
+}
+
+/// Accepts `a=5, b=8` with the numbers in either order, or just `5, 8`.
+fn parse_affine_key(key: &str) -> Result<AffineKey, String> {
+    let parts: Vec<&str> = key.split([',', ' '])
+        .filter(|part| !part.is_empty())
+        .collect();
+    let [first, second] = parts.as_slice() else {
+        return Err("expected two numbers, e.g. `a=5, b=8`".to_owned());
+    };
+
+    let parse = |value: &str| value.parse::<usize>().map_err(|_| format!("`{}` is not a number", value));
+    match (first.split_once('='), second.split_once('=')) {
+        (None, None) => AffineKey::new(parse(first)?, parse(second)?),
+        (Some(first), Some(second)) => {
+            let (mut a, mut b) = (None, None);
+            for (name, value) in [first, second] {
+                let slot = match name.to_ascii_lowercase().as_str() {
+                    "a" => &mut a,
+                    "b" => &mut b,
+                    _ => return Err(format!("unknown key part `{}`, expected `a` or `b`", name)),
+                };
+                if slot.replace(parse(value)?).is_some() {
+                    return Err(format!("`{}` is given twice", name));
+                }
+            }
+            // Two distinct labels, so both are set
+            AffineKey::new(a.unwrap_or_default(), b.unwrap_or_default())
+        }
+        _ => Err("label both numbers, e.g. `a=5, b=8`, or neither".to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+
+    #[test]
+    fn labelled_key_parts_are_read_by_name() {
+        let key = AffineKey::new(5, 3).unwrap();
+        assert_eq!(parse_affine_key("b=3, a=5"), Ok(key));
+        assert_eq!(parse_affine_key("a=5, b=3"), Ok(key));
+        assert_eq!(parse_affine_key("5, 3"), Ok(key));
+        assert!(parse_affine_key("a=5, a=3").is_err());
+        assert!(parse_affine_key("a=5, 3").is_err());
+    }
+
+    #[test]
+    fn rejects_a_not_coprime_to_26() {
+        assert!(AffineKey::new(2, 0).is_err());
+        assert!(AffineKey::new(13, 4).is_err());
+    }
+
+    #[test]
+    fn every_valid_key_decrypts_with_its_inverse() {
+        let key = AffineKey::new(5, 8).unwrap();
+        assert_eq!(Affine.encrypt(&key, "AFFINECIPHER"), "IHHWVCSWFRCP");
+
+        let keys: Vec<AffineKey> = (0..26).flat_map(|a| (0..26).filter_map(move |b| AffineKey::new(a, b).ok())).collect();
+        assert_eq!(keys.len(), 312);
+        for key in keys {
+            assert_eq!(Affine.decrypt(&key, &Affine.encrypt(&key, "Affine, 1 cipher")), "Affine, 1 cipher");
+        }
+    }
+
+    #[test]
+    fn attack_names_the_key_by_a_and_b() {
+        let ciphertext = Affine.encrypt(&AffineKey::new(7, 20).unwrap(), SAMPLE_TEXT);
+        let candidates = Affine.attack(&ciphertext, &AttackParams::default());
+        assert_eq!(candidates[0].text, format!("Key: a=7, b=20\nDecryption:\n{}", SAMPLE_TEXT));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::cipher::{format_order, parse_permutation, AttackParams, CandidateHeap, Cipher, Setting};
+use crate::{Candidate, CipherType};
+
+use super::columnar::{read_off, write_back};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+
+pub struct Amsco;
+
+/// Column order as in a columnar key, and whether the first cell holds two letters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmscoKey {
+    pub order: Vec<usize>,
+    pub pair_first: bool,
+}
+
+impl Cipher for Amsco {
+    type Key = AmscoKey;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Amsco
+    }
+
+    fn id(&self) -> &'static str {
+        "amsco"
+    }
+
+    fn name(&self) -> &'static str {
+        "AMSCO"
+    }
+
+    /// `2,0,1` or `2,0,1;2`: the column order, then optionally the size of the first cell.
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<AmscoKey, String> {
+        let (order, first) = key.split_once(';').unwrap_or((key, "1"));
+        let pair_first = match first.trim() {
+            "1" => false,
+            "2" => true,
+            other => return Err(format!("the first cell holds 1 or 2 letters, not `{}`", other)),
+        };
+        Ok(AmscoKey { order: parse_permutation(order)?, pair_first })
+    }
+
+    fn encrypt(&self, key: &AmscoKey, plaintext: &str) -> String {
+        amsco_enc(plaintext, &key.order, key.pair_first)
+    }
+
+    fn decrypt(&self, key: &AmscoKey, ciphertext: &str) -> String {
+        amsco_inv(ciphertext, &key.order, key.pair_first)
+    }
+
+    fn settings(&self, _params: &AttackParams) -> Vec<Setting> {
+        vec![Setting::MaxKeyLength("Max Key Length")]
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        let heap = CandidateHeap::new(3); // Keep only top 3 candidates
+
+        (2..=params.max_key_length).into_par_iter().for_each(|key_length| {
+            for order in (0..key_length).permutations(key_length) {
+                for pair_first in [false, true] {
+                    let decrypted_text = amsco_inv(text, &order, pair_first);
+                    let score = params.scorer.score(&decrypted_text);
+                    heap.push(score, decrypted_text, AmscoKey { order: order.clone(), pair_first });
+                }
+            }
+        });
+
+        heap.into_candidates(|key, text| {
+            format!("Key: {}, first cell {}\nText: {}", format_order(&key.order), if key.pair_first { 2 } else { 1 }, text)
+        })
+    }
+}
+
+/// Plaintext positions in reading order. The text fills the grid row by row in cells of
+/// one and two letters, alternating along each row, with each row starting with the other
+/// size from the row above. Columns are then read in key order, top to bottom.
+fn reading_positions(n: usize, order: &[usize], pair_first: bool) -> Vec<usize> {
+    let width = order.len();
+
+    // (column, row, first position, length) of every cell
+    let mut cells = Vec::new();
+    let (mut start, mut row) = (0, 0);
+    while start < n {
+        let mut pair = pair_first ^ (row % 2 == 1);
+        for col in 0..width {
+            if start >= n {
+                break;
+            }
+            let len = if pair { 2 } else { 1 }.min(n - start);
+            cells.push((col, row, start, len));
+            start += len;
+            pair = !pair;
+        }
+        row += 1;
+    }
+
+    cells.sort_by_key(|&(col, row, _, _)| (order[col], row));
+    cells.into_iter().flat_map(|(_, _, start, len)| start..start + len).collect()
+}
+
+pub fn amsco_enc(text: &str, order: &[usize], pair_first: bool) -> String {
+    read_off(text, reading_positions(text.chars().count(), order, pair_first))
+}
+
+pub fn amsco_inv(text: &str, order: &[usize], pair_first: bool) -> String {
+    write_back(text, reading_positions(text.chars().count(), order, pair_first))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+    use crate::scoring::QuadgramScorer;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn cells_alternate_along_rows_and_down_columns() {
+        // Rows A BC D / EF G HI, read column 1, then 0, then 2
+        assert_eq!(amsco_enc("ABCDEFGHI", &[1, 0, 2], false), "BCGAEFDHI");
+        // Rows AB C DE / F GH I
+        assert_eq!(amsco_enc("ABCDEFGHI", &[1, 0, 2], true), "CGHABFDEI");
+        // The last cell is cut short
+        assert_eq!(amsco_enc("ABCDEFGH", &[1, 0, 2], false), "BCGAEFDH");
+        assert_eq!(amsco_inv("BCGAEFDH", &[1, 0, 2], false), "ABCDEFGH");
+    }
+
+    #[test]
+    fn attack_recovers_the_order_and_first_cell() {
+        let key = Amsco.parse_key("3,1,0,2;2", &AttackParams::default()).unwrap();
+        assert_eq!(key, AmscoKey { order: vec![3, 1, 0, 2], pair_first: true });
+        let ciphertext = Amsco.encrypt(&key, SAMPLE_TEXT);
+        let params = AttackParams { max_key_length: 5, scorer: Arc::new(QuadgramScorer), ..AttackParams::default() };
+
+        let candidates = Amsco.attack(&ciphertext, &params);
+        let (key, text) = candidates[0].text.split_once('\n').unwrap();
+        assert!(key.starts_with("Key: [3, 1, 0, 2] ") && key.ends_with(", first cell 2"), "{}", key);
+        assert_eq!(text, format!("Text: {}", SAMPLE_TEXT));
+    }
+}
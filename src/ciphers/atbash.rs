@@ -0,0 +1,69 @@
+use crate::cipher::{AttackParams, Cipher};
+use crate::{Candidate, CipherType};
+
+use super::Beaufort;
+
+pub struct Atbash;
+
+impl Cipher for Atbash {
+    /// Atbash has no key.
+    type Key = ();
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Atbash
+    }
+
+    fn id(&self) -> &'static str {
+        "atbash"
+    }
+
+    fn name(&self) -> &'static str {
+        "Atbash"
+    }
+
+    fn parse_key(&self, _key: &str, _params: &AttackParams) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn encrypt(&self, key: &(), plaintext: &str) -> String {
+        self.decrypt(key, plaintext)
+    }
+
+    fn decrypt(&self, _key: &(), ciphertext: &str) -> String {
+        // Reversing the alphabet is a Beaufort cipher with the single key letter `z`
+        Beaufort.decrypt(&vec![25], ciphertext)
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        // Nothing to search: score the only decryption so it can be compared with other ciphers
+        let decrypted_text = self.decrypt(&(), text);
+        vec![Candidate {
+            name: "Candidate 1".to_string(),
+            score: params.scorer.score(&decrypted_text),
+            text: format!("Key: (none)\nDecryption:\n{}", decrypted_text),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+
+    #[test]
+    fn reverses_the_alphabet_and_is_its_own_inverse() {
+        assert_eq!(Atbash.encrypt(&(), "Hello, World!"), "Svool, Dliow!");
+        assert_eq!(Atbash.encrypt(&(), "abcxyz"), "zyxcba");
+
+        let ciphertext = Atbash.encrypt(&(), SAMPLE_TEXT);
+        assert_eq!(Atbash.encrypt(&(), &ciphertext), SAMPLE_TEXT);
+    }
+
+    #[test]
+    fn attack_scores_the_only_decryption() {
+        let ciphertext = Atbash.encrypt(&(), SAMPLE_TEXT);
+        let candidates = Atbash.attack(&ciphertext, &AttackParams::default());
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].text, format!("Key: (none)\nDecryption:\n{}", SAMPLE_TEXT));
+    }
+}
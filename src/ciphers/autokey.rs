@@ -0,0 +1,144 @@
+use crate::cipher::{parse_shift_key, AttackParams, CandidateHeap, Cipher, Setting};
+use crate::scoring::{letter_indices, quadgram_score_indices};
+use crate::{Candidate, CipherType};
+
+use super::vigenere::{key_to_string, shift_letter, unshift_letter};
+
+use rayon::prelude::*;
+
+pub struct Autokey;
+
+impl Cipher for Autokey {
+    /// The primer, one shift per letter, `a` = 0. The plaintext follows it in the key stream.
+    type Key = Vec<usize>;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Autokey
+    }
+
+    fn id(&self) -> &'static str {
+        "autokey"
+    }
+
+    fn name(&self) -> &'static str {
+        "Autokey Vigenère"
+    }
+
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<Vec<usize>, String> {
+        parse_shift_key(key)
+    }
+
+    fn encrypt(&self, key: &Vec<usize>, plaintext: &str) -> String {
+        autokey_text(plaintext, key, |shift, letter| (shift_letter(shift, letter), letter))
+    }
+
+    fn decrypt(&self, key: &Vec<usize>, ciphertext: &str) -> String {
+        autokey_text(ciphertext, key, |shift, letter| {
+            let plaintext = unshift_letter(shift, letter);
+            (plaintext, plaintext)
+        })
+    }
+
+    fn settings(&self, _params: &AttackParams) -> Vec<Setting> {
+        vec![Setting::MaxKeyLength("Max Primer Length")]
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        let letters = letter_indices(text);
+
+        let heap = CandidateHeap::new(5);
+        (1..=params.max_key_length).into_par_iter().for_each(|length| {
+            let (score, primer) = climb_primer(&letters, length);
+            params.log(format_args!("Best primer of length {}: {} (quadgram score {:.3})",
+                length, key_to_string(&primer), score));
+
+            let result = self.decrypt(&primer, text);
+            let score = params.scorer.score(&result);
+            heap.push(score, result, primer);
+        });
+
+        heap.into_candidates(|key, text| format!("Key: {}\nDecryption:\n{}", key_to_string(key), text))
+    }
+}
+
+/// Runs the key stream over the letters of `text`, keeping case and passing everything else
+/// through. `f(shift, letter)` returns the output letter and the plaintext letter that joins
+/// the end of the key stream.
+fn autokey_text(text: &str, primer: &[usize], f: impl Fn(u8, u8) -> (u8, u8)) -> String {
+    let mut stream: Vec<u8> = primer.iter().map(|&shift| shift as u8).collect();
+    let mut position = 0;
+    text.chars().map(|c| {
+        if !c.is_ascii_alphabetic() {
+            return c;
+        }
+        let base = if c.is_uppercase() { 'A' } else { 'a' } as u8;
+        let (output, plaintext) = f(stream[position], c as u8 - base);
+        stream.push(plaintext);
+        position += 1;
+        (output + base) as char
+    }).collect()
+}
+
+/// Decrypts ciphertext already turned into alphabet positions.
+fn decrypt_indices(letters: &[usize], primer: &[usize]) -> Vec<usize> {
+    let mut plaintext: Vec<usize> = Vec::with_capacity(letters.len());
+    for (i, &letter) in letters.iter().enumerate() {
+        let shift = if i < primer.len() { primer[i] } else { plaintext[i - primer.len()] };
+        plaintext.push(unshift_letter(shift as u8, letter as u8) as usize);
+    }
+    plaintext
+}
+
+/// Starting from a primer of `a`s, sets each position in turn to the letter with the best
+/// quadgram score, repeating until no position changes. A primer letter only affects every
+/// `length`th plaintext letter, so the positions can be solved almost independently.
+fn climb_primer(letters: &[usize], length: usize) -> (f32, Vec<usize>) {
+    let mut primer = vec![0; length];
+    let mut best_score = quadgram_score_indices(&decrypt_indices(letters, &primer));
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for position in 0..length {
+            for shift in 0..26 {
+                if shift == primer[position] {
+                    continue;
+                }
+                let mut candidate = primer.clone();
+                candidate[position] = shift;
+                let score = quadgram_score_indices(&decrypt_indices(letters, &candidate));
+                if score > best_score {
+                    best_score = score;
+                    primer = candidate;
+                    improved = true;
+                }
+            }
+        }
+    }
+    (best_score, primer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+
+    #[test]
+    fn key_stream_advances_over_letters_only() {
+        let key = parse_shift_key("queenly").unwrap();
+        assert_eq!(Autokey.encrypt(&key, "ATTACKATDAWN"), "QNXEPVYTWTWP");
+        assert_eq!(Autokey.encrypt(&key, "Attack at dawn!"), "Qnxepv yt wtwp!");
+        assert_eq!(Autokey.decrypt(&key, "Qnxepv yt wtwp!"), "Attack at dawn!");
+    }
+
+    #[test]
+    fn climb_recovers_the_primer() {
+        let primer = parse_shift_key("queenly").unwrap();
+        let letters = letter_indices(&Autokey.encrypt(&primer, SAMPLE_TEXT));
+        assert_eq!(decrypt_indices(&letters, &primer), letter_indices(SAMPLE_TEXT));
+
+        let (score, found) = climb_primer(&letters, primer.len());
+        assert_eq!(key_to_string(&found), "queenly");
+        assert_eq!(score, quadgram_score_indices(&letter_indices(SAMPLE_TEXT)));
+    }
+}
@@ -0,0 +1,108 @@
+use crate::cipher::{parse_shift_key, AttackParams, Cipher, Setting};
+use crate::{Candidate, CipherType};
+
+use super::vigenere::{column_keys, dictionary_attack, rank_keys, shift_text};
+
+pub struct Beaufort;
+
+impl Cipher for Beaufort {
+    /// One shift per key letter, `a` = 0.
+    type Key = Vec<usize>;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Beaufort
+    }
+
+    fn id(&self) -> &'static str {
+        "beaufort"
+    }
+
+    fn name(&self) -> &'static str {
+        "Beaufort Cipher"
+    }
+
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<Vec<usize>, String> {
+        parse_shift_key(key)
+    }
+
+    fn encrypt(&self, key: &Vec<usize>, plaintext: &str) -> String {
+        // Beaufort is reciprocal: the same operation encrypts and decrypts
+        self.decrypt(key, plaintext)
+    }
+
+    fn decrypt(&self, key: &Vec<usize>, ciphertext: &str) -> String {
+        shift_text(ciphertext, key, beaufort_letter)
+    }
+
+    fn settings(&self, params: &AttackParams) -> Vec<Setting> {
+        let mut settings = if params.auto_period || params.dictionary_attack {
+            vec![Setting::MaxKeyLength("Max Period")]
+        } else {
+            vec![Setting::Period]
+        };
+        // The dictionary attack replaces the frequency analysis the period detection steers
+        if !params.dictionary_attack {
+            settings.push(Setting::AutoPeriod);
+        }
+        settings.push(Setting::Dictionary);
+        settings
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        if params.dictionary_attack {
+            return dictionary_attack(text, params, |key, text| self.decrypt(key, text));
+        }
+
+        // Each column is ranked by chi-squared over all 26 shifts, as for Vigenère
+        let key_candidates = column_keys(text, params, 0, beaufort_letter, |key, text| self.decrypt(key, text));
+        rank_keys(text, key_candidates, params, |key, text| self.decrypt(key, text))
+    }
+}
+
+/// Beaufort encryption and decryption of one letter, both 0-25.
+fn beaufort_letter(shift: u8, letter: u8) -> u8 {
+    (shift + 26 - letter) % 26
+}
+
+/// Helper function to calculate what letter in the key would transform a given ciphertext letter to 'E' in Beaufort cipher
+/// Returns the key letter that would transform ciphertext_letter to 'E'
+/// Using the formula from the working decryption: decrypted = (shift + 26 - (ciphertext - base)) % 26 + base
+pub fn beaufort_key_letter(ciphertext_letter: char) -> char {
+    let base = if ciphertext_letter.is_uppercase() { 'A' } else { 'a' } as u8;
+    let cipher_pos = (ciphertext_letter as u8 - base) as usize;
+    let e_pos = 4; // 'E' is position 4 (0-based)
+
+    // We want: e_pos = (key_pos + 26 - cipher_pos) % 26
+    // Therefore: key_pos = (e_pos + cipher_pos) % 26
+    let key_pos = (e_pos + cipher_pos) % 26;
+    (key_pos as u8 + b'a') as char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::{keeps_layout, SAMPLE_TEXT};
+
+    #[test]
+    fn auto_period_recovers_the_key() {
+        let key = parse_shift_key("lemon").unwrap();
+        let ciphertext = Beaufort.encrypt(&key, SAMPLE_TEXT);
+        let params = AttackParams { auto_period: true, ..AttackParams::default() };
+
+        let candidates = Beaufort.attack(&ciphertext, &params);
+        assert!(candidates[0].text.starts_with("Key: lemon\n"));
+    }
+
+    #[test]
+    fn round_trip_keeps_case_and_punctuation() {
+        let key = parse_shift_key("fortification").unwrap();
+        assert_eq!(Beaufort.encrypt(&key, "DEFENDTHEEASTWALLOFTHECASTLE"), "CKMPVCPVWPIWUJOGIUAPVWRIWUUK");
+        assert_eq!(Beaufort.encrypt(&key, "Defend the east wall of the castle"), "Ckmpvc pvw piwu jogi ua pvw riwuuk");
+
+        let key = parse_shift_key("lemon").unwrap();
+        let ciphertext = Beaufort.encrypt(&key, SAMPLE_TEXT);
+        assert_ne!(ciphertext, SAMPLE_TEXT);
+        assert!(keeps_layout(SAMPLE_TEXT, &ciphertext));
+        assert_eq!(Beaufort.decrypt(&key, &ciphertext), SAMPLE_TEXT);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::cipher::{AttackParams, CandidateHeap, Cipher};
+use crate::{Candidate, CipherType};
+
+use super::Vigenere;
+
+pub struct Caesar;
+
+impl Cipher for Caesar {
+    /// The shift, 0-25.
+    type Key = usize;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Caesar
+    }
+
+    fn id(&self) -> &'static str {
+        "caesar"
+    }
+
+    fn name(&self) -> &'static str {
+        "Caesar / ROT-N"
+    }
+
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<usize, String> {
+        parse_caesar_key(key)
+    }
+
+    fn encrypt(&self, key: &usize, plaintext: &str) -> String {
+        // A Caesar shift is a Vigenère cipher with period 1
+        Vigenere.encrypt(&vec![*key], plaintext)
+    }
+
+    fn decrypt(&self, key: &usize, ciphertext: &str) -> String {
+        Vigenere.decrypt(&vec![*key], ciphertext)
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        let heap = CandidateHeap::new(5);
+        for shift in 0..26 {
+            let decrypted_text = self.decrypt(&shift, text);
+            let score = params.scorer.score(&decrypted_text);
+            heap.push(score, decrypted_text, shift);
+        }
+
+        heap.into_candidates(|&shift, text| {
+            format!("Key: ROT-{} ({})\nDecryption:\n{}", shift, (shift as u8 + b'a') as char, text)
+        })
+    }
+}
+
+/// Accepts the shift as a number (`3`) or as the letter `a` maps to (`d`).
+fn parse_caesar_key(key: &str) -> Result<usize, String> {
+    let key = key.trim();
+    if let Ok(shift) = key.parse::<usize>() {
+        return Ok(shift % 26);
+    }
+
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Ok((c.to_ascii_lowercase() as u8 - b'a') as usize),
+        (None, _) => Err("the key is empty".to_owned()),
+        _ => Err(format!("`{}` is not a shift (expected a number or a single letter)", key)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+
+    #[test]
+    fn keys_are_numbers_or_the_letter_a_maps_to() {
+        assert_eq!(parse_caesar_key("3"), Ok(3));
+        assert_eq!(parse_caesar_key("29"), Ok(3));
+        assert_eq!(parse_caesar_key(" D "), Ok(3));
+        assert!(parse_caesar_key("").is_err());
+        assert!(parse_caesar_key("de").is_err());
+    }
+
+    #[test]
+    fn shifts_letters_only() {
+        assert_eq!(Caesar.encrypt(&3, "The quick brown fox, 1 day."), "Wkh txlfn eurzq ira, 1 gdb.");
+        assert_eq!(Caesar.decrypt(&3, "Wkh txlfn eurzq ira, 1 gdb."), "The quick brown fox, 1 day.");
+    }
+
+    #[test]
+    fn attack_ranks_the_true_shift_first() {
+        let ciphertext = Caesar.encrypt(&19, SAMPLE_TEXT);
+        let candidates = Caesar.attack(&ciphertext, &AttackParams::default());
+        assert!(candidates[0].text.starts_with("Key: ROT-19 (t)\n"));
+        assert!(candidates[0].text.ends_with(SAMPLE_TEXT));
+    }
+}
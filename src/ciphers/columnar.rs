@@ -0,0 +1,449 @@
+use crate::cipher::{format_order, parse_permutation, task_rngs, AttackParams, CandidateHeap, Cipher, Setting};
+use crate::scoring::{bigram_log_prob, quadgram_score_indices};
+use crate::{compute_factors, Candidate, CipherType};
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+
+// A climb stops after this many moves in a row fail to improve the score
+const MAX_STALE_MOVES: usize = 5_000;
+
+pub struct Columnar;
+
+/// How the columnar attack looks for the key.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnarSearch {
+    /// Every permutation of every key length, only practical up to about 10 columns
+    Exhaustive,
+    /// Hill climbing from random keys with swap, slide and reverse moves
+    HillClimb,
+    /// Chains columns by how well their letters pair up as bigrams, then polishes the best
+    /// chains with short climbs
+    Adjacency,
+}
+
+impl ColumnarSearch {
+    pub const ALL: [ColumnarSearch; 3] = [ColumnarSearch::Exhaustive, ColumnarSearch::HillClimb, ColumnarSearch::Adjacency];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            ColumnarSearch::Exhaustive => "exhaustive",
+            ColumnarSearch::HillClimb => "climb",
+            ColumnarSearch::Adjacency => "adjacency",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColumnarSearch::Exhaustive => "Exhaustive",
+            ColumnarSearch::HillClimb => "Hill Climbing",
+            ColumnarSearch::Adjacency => "Column Adjacency",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<ColumnarSearch> {
+        Self::ALL.into_iter().find(|search| search.id() == id)
+    }
+
+    /// Adjacency needs rows of consecutive plaintext, which a transposed grid does not have.
+    pub fn supports_transpose(self) -> bool {
+        self != ColumnarSearch::Adjacency
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnarKey {
+    pub order: Vec<usize>,
+    pub transpose: bool,
+}
+
+impl Cipher for Columnar {
+    type Key = ColumnarKey;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Columnar
+    }
+
+    fn id(&self) -> &'static str {
+        "columnar"
+    }
+
+    fn name(&self) -> &'static str {
+        "Columnar Transposition"
+    }
+
+    fn parse_key(&self, key: &str, params: &AttackParams) -> Result<ColumnarKey, String> {
+        Ok(ColumnarKey { order: parse_permutation(key)?, transpose: params.transpose })
+    }
+
+    fn encrypt(&self, key: &ColumnarKey, plaintext: &str) -> String {
+        columnar_enc(plaintext, &key.order, key.transpose)
+    }
+
+    fn decrypt(&self, key: &ColumnarKey, ciphertext: &str) -> String {
+        columnar_inv(ciphertext, &key.order, key.transpose)
+    }
+
+    fn settings(&self, params: &AttackParams) -> Vec<Setting> {
+        let mut settings = vec![Setting::Transpose, Setting::MaxKeyLength("Max Key Length"), Setting::ColumnarSearch];
+        if params.columnar_search != ColumnarSearch::Exhaustive {
+            settings.push(Setting::Restarts);
+        }
+        settings
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        self.attack_with_rng(text, params, &mut rand::rng())
+    }
+}
+
+impl Columnar {
+    /// `attack` with the climbs drawn from `rng`.
+    fn attack_with_rng(&self, text: &str, params: &AttackParams, rng: &mut impl Rng) -> Vec<Candidate> {
+        let heap = CandidateHeap::new(3); // Keep only top 3 candidates
+
+        // Find the factors of the text length
+        let factors = compute_factors(text.chars().count());
+        params.log(format_args!("Factors: {:?}", factors));
+
+        let search = match params.columnar_search {
+            search if params.transpose && !search.supports_transpose() => {
+                params.log(format_args!("{} does not work on a transposed grid, hill climbing instead", search.name()));
+                ColumnarSearch::HillClimb
+            }
+            search => search,
+        };
+
+        match search {
+            ColumnarSearch::Exhaustive => {
+                // Process key lengths in parallel
+                (1..=params.max_key_length).into_par_iter().for_each(|key_length| {
+                    let permutations = (0..key_length).permutations(key_length);
+
+                    // For each permutation in this key length
+                    for permutation in permutations {
+                        let decrypted_text = columnar_inv(text, &permutation, params.transpose);
+                        let score = params.scorer.score(&decrypted_text);
+                        heap.push(score, decrypted_text, permutation);
+                    }
+                });
+            }
+            ColumnarSearch::HillClimb => {
+                let lengths: Vec<(usize, StdRng)> = (2..=params.max_key_length)
+                    .zip(task_rngs(rng, params.max_key_length.saturating_sub(1)))
+                    .collect();
+                lengths.into_par_iter().for_each(|(key_length, mut rng)| {
+                    for _ in 0..params.restarts.max(1) {
+                        let mut key: Vec<usize> = (0..key_length).collect();
+                        key.shuffle(&mut rng);
+                        let (_, key) = climb_order(vec![key], order_fitness(text, params.transpose), &mut rng);
+                        let decrypted_text = columnar_inv(text, &key, params.transpose);
+                        let score = params.scorer.score(&decrypted_text);
+                        heap.push(score, decrypted_text, key);
+                    }
+                });
+            }
+            ColumnarSearch::Adjacency => {
+                let lengths: Vec<(usize, StdRng)> = (2..=params.max_key_length)
+                    .zip(task_rngs(rng, params.max_key_length.saturating_sub(1)))
+                    .collect();
+                lengths.into_par_iter().for_each(|(key_length, mut rng)| {
+                    // Each restart climbs from the next best chain. The chain can begin at
+                    // any column and the wrap-around pair looks adjacent too, one row apart,
+                    // so a climb starts from the best rotation of its chain
+                    let chains = adjacency_chains(text, key_length);
+                    for chain in chains.iter().cycle().take(params.restarts.max(1)) {
+                        let rotations = (0..key_length)
+                            .map(|shift| {
+                                let mut rotated = chain.clone();
+                                rotated.rotate_left(shift);
+                                rotated
+                            })
+                            .collect();
+                        let (_, key) = climb_order(rotations, order_fitness(text, false), &mut rng);
+                        let decrypted_text = columnar_inv(text, &key, false);
+                        let score = params.scorer.score(&decrypted_text);
+                        heap.push_distinct(score, decrypted_text, key);
+                    }
+                });
+            }
+        }
+
+        heap.into_candidates(|key, text| format!("Key: {}\nText: {}", format_order(key), text))
+    }
+}
+
+/// Keys from chains of columns, best first. Adjacent columns of the grid hold consecutive
+/// plaintext letters in every row, so column `a` is scored as the left neighbour of column
+/// `b` by the bigram log probabilities of their letters side by side. In an uneven grid
+/// where each column starts in the ciphertext depends on which columns are long, so every
+/// start a column could have is tried and the pair keeps its best alignment.
+fn adjacency_chains(text: &str, key_length: usize) -> Vec<Vec<usize>> {
+    // One entry per char, as `columnar_inv` lays the grid out, with 26 for anything else
+    let letters: Vec<usize> = text.chars()
+        .map(|c| if c.is_ascii_alphabetic() { (c.to_ascii_lowercase() as u8 - b'a') as usize } else { 26 })
+        .collect();
+    let n = letters.len();
+    let rows = n / key_length;
+    let long_columns = n % key_length;
+
+    // Segment `k` is the column read `k`-th. Anywhere from the fewest to the most long
+    // columns the key allows can be read before it.
+    let starts = |k: usize| {
+        let fewest = (k + long_columns).saturating_sub(key_length);
+        (k * rows + fewest)..=(k * rows + k.min(long_columns))
+    };
+    let pair_score = |a: usize, b: usize| -> f32 {
+        letters[a..a + rows].iter().zip(&letters[b..b + rows])
+            .filter(|&(&x, &y)| x < 26 && y < 26)
+            .map(|(&x, &y)| bigram_log_prob(x, y))
+            .sum()
+    };
+
+    let adjacency: Vec<Vec<f32>> = (0..key_length)
+        .map(|a| (0..key_length)
+            .map(|b| if a == b {
+                0.0
+            } else {
+                starts(a).cartesian_product(starts(b))
+                    .map(|(start_a, start_b)| pair_score(start_a, start_b))
+                    .fold(f32::NEG_INFINITY, f32::max)
+            })
+            .collect())
+        .collect();
+
+    // Greedily extend a chain from every starting column
+    let mut chains: Vec<(f32, Vec<usize>)> = (0..key_length)
+        .map(|first| {
+            let mut chain = vec![first];
+            let mut total = 0.0;
+            while chain.len() < key_length {
+                let last = *chain.last().unwrap();
+                let next = (0..key_length)
+                    .filter(|k| !chain.contains(k))
+                    .max_by(|&x, &y| adjacency[last][x].total_cmp(&adjacency[last][y]))
+                    .unwrap();
+                total += adjacency[last][next];
+                chain.push(next);
+            }
+            (total, chain)
+        })
+        .collect();
+    chains.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    // Column `i` of the grid is the segment read `chain[i]`-th, which is exactly the key
+    chains.into_iter().map(|(_, chain)| chain).unique().collect()
+}
+
+/// Quadgram score of `text` decrypted with a column order, without building the string.
+pub(crate) fn order_fitness(text: &str, transpose: bool) -> impl FnMut(&[usize]) -> f32 + '_ {
+    let chars: Vec<char> = text.chars().collect();
+    let n = chars.len();
+    let mut output = vec!['\0'; n];
+    let mut plaintext = Vec::with_capacity(n);
+
+    move |key: &[usize]| {
+        let positions = inverse_key(key)
+            .into_iter()
+            .flat_map(|col| column_positions(n, key.len(), col, transpose));
+        for (c_index, i) in positions.enumerate() {
+            output[i] = chars[c_index];
+        }
+        plaintext.clear();
+        plaintext.extend(output.iter()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| (c.to_ascii_lowercase() as u8 - b'a') as usize));
+        quadgram_score_indices(&plaintext)
+    }
+}
+
+/// Climbs over column orders from the best of the `starts` keys, all of one length, until
+/// `MAX_STALE_MOVES` moves in a row bring no improvement in `fitness`. Moves swap two
+/// columns, slide a block of columns elsewhere or reverse a block; the last two keep most
+/// adjacent column pairs intact, and are drawn from `rng`. Returns the final score and key.
+pub(crate) fn climb_order(
+    starts: Vec<Vec<usize>>,
+    mut fitness: impl FnMut(&[usize]) -> f32,
+    rng: &mut impl Rng,
+) -> (f32, Vec<usize>) {
+    let key_length = starts[0].len();
+
+    let (mut best, mut key) = starts.into_iter()
+        .map(|key| (fitness(&key), key))
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .unwrap();
+
+    let mut stale = 0;
+    while stale < MAX_STALE_MOVES {
+        let mut child = key.clone();
+        let (i, j) = (rng.random_range(0..key_length), rng.random_range(0..key_length));
+        let (start, end) = (i.min(j), i.max(j) + 1);
+        match rng.random_range(0..3) {
+            0 => child.swap(i, j),
+            1 => {
+                let block: Vec<usize> = child.drain(start..end).collect();
+                let at = rng.random_range(0..=child.len());
+                child.splice(at..at, block);
+            }
+            _ => child[start..end].reverse(),
+        }
+
+        // Equal scores are accepted so the climb can wander across plateaus
+        let score = fitness(&child);
+        stale = if score > best { 0 } else { stale + 1 };
+        if score >= best {
+            key = child;
+            best = score;
+        }
+    }
+    (best, key)
+}
+
+/// Column index `col` of the grid holds the plaintext positions returned here, top to bottom.
+fn column_positions(n: usize, k_l: usize, col: usize, transpose: bool) -> impl Iterator<Item = usize> {
+    let s_l = n / k_l;
+    // |s|, number of letters in each column, rounded down, i.e. num rows
+    let r = n % k_l;
+    // this is the length of the last row, also num columns with an extra row
+    let row_len = if col < r { s_l + 1 } else { s_l };
+
+    (0..row_len).map(move |row| {
+        if transpose {
+            col * s_l + row + (if col < r { col } else { r })
+        } else {
+            row * k_l + col
+        }
+    })
+}
+
+/// Reading order of the columns: the column holding key value 0 first, then 1, ...
+fn inverse_key(key: &[usize]) -> Vec<usize> {
+    let mut key_inv = vec![0; key.len()];
+    for (i, &k) in key.iter().enumerate() {
+        key_inv[k] = i;
+    }
+    key_inv
+}
+
+/// Transposition encryption given the plaintext positions in the order they are read off.
+pub(crate) fn read_off(text: &str, positions: impl IntoIterator<Item = usize>) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    positions.into_iter().map(|i| chars[i]).collect()
+}
+
+/// Undoes `read_off`: the ciphertext characters go back to the positions they were read from.
+pub(crate) fn write_back(text: &str, positions: impl IntoIterator<Item = usize>) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut output = vec!['\0'; chars.len()];
+    for (c_index, i) in positions.into_iter().enumerate() {
+        output[i] = chars[c_index];
+    }
+    output.into_iter().collect()
+}
+
+pub fn columnar_enc(text: &str, key: &[usize], transpose: bool) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let n = chars.len();
+
+    inverse_key(key)
+        .into_iter()
+        .flat_map(|col| column_positions(n, key.len(), col, transpose))
+        .map(|i| chars[i])
+        .collect()
+}
+
+pub fn columnar_inv(text: &str, key: &[usize], transpose: bool) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let n = chars.len();
+    let mut output = vec!['\0'; n];
+
+    let positions = inverse_key(key)
+        .into_iter()
+        .flat_map(|col| column_positions(n, key.len(), col, transpose));
+    for (c_index, i) in positions.enumerate() {
+        output[i] = chars[c_index];
+    }
+
+    output.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::keyword_order;
+    use crate::ciphers::SAMPLE_TEXT;
+    use crate::scoring::{indices_to_text, letter_indices, quadgram_score, QuadgramScorer};
+
+    use std::sync::Arc;
+
+    #[test]
+    fn adjacency_solves_long_keys_on_uneven_grids() {
+        use rand::SeedableRng;
+
+        let plaintext = indices_to_text(&letter_indices(SAMPLE_TEXT));
+        let params = AttackParams {
+            columnar_search: ColumnarSearch::Adjacency,
+            max_key_length: 20,
+            restarts: 3,
+            scorer: Arc::new(QuadgramScorer),
+            ..AttackParams::default()
+        };
+        for seed in 0..4 {
+            let mut key: Vec<usize> = (0..20).collect();
+            key.shuffle(&mut StdRng::seed_from_u64(seed));
+            assert!(!plaintext.len().is_multiple_of(key.len()));
+            let ciphertext = columnar_enc(&plaintext, &key, false);
+
+            let candidates = Columnar.attack_with_rng(&ciphertext, &params, &mut StdRng::seed_from_u64(seed));
+            assert!(candidates[0].text.ends_with(&plaintext), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn hill_climbing_solves_keys_too_long_to_enumerate() {
+        use rand::SeedableRng;
+
+        let plaintext = indices_to_text(&letter_indices(SAMPLE_TEXT));
+        let key = keyword_order("blackfriarsdoc");
+        let ciphertext = columnar_enc(&plaintext, &key, false);
+
+        let params = AttackParams {
+            columnar_search: ColumnarSearch::HillClimb,
+            max_key_length: 14,
+            restarts: 4,
+            scorer: Arc::new(QuadgramScorer),
+            ..AttackParams::default()
+        };
+        let candidates = Columnar.attack_with_rng(&ciphertext, &params, &mut StdRng::seed_from_u64(3260));
+        assert!(candidates[0].text.ends_with(&plaintext));
+    }
+
+    #[test]
+    fn fitness_scores_the_grid_decryption_builds() {
+        let plaintext = SAMPLE_TEXT.replace("coal", "café coal").replace("inn", "naïve inn");
+        let key = keyword_order("zebras");
+        for transpose in [false, true] {
+            let ciphertext = columnar_enc(&plaintext, &key, transpose);
+            let decrypted = columnar_inv(&ciphertext, &key, transpose);
+            assert_eq!(order_fitness(&ciphertext, transpose)(&key), quadgram_score(&decrypted));
+        }
+    }
+
+    #[test]
+    fn round_trip_on_uneven_grids() {
+        let zebras = parse_permutation("zebras").unwrap();
+        assert_eq!(columnar_enc("WEAREDISCOVEREDFLEEATONCE", &zebras, false), "EVLNACDTESEAROFODEECWIREE");
+
+        for key in [zebras, vec![3, 0, 4, 1, 2], vec![6, 2, 0, 5, 1, 3, 4]] {
+            assert!(!SAMPLE_TEXT.len().is_multiple_of(key.len()));
+            for transpose in [false, true] {
+                let ciphertext = columnar_enc(SAMPLE_TEXT, &key, transpose);
+                assert_ne!(ciphertext, SAMPLE_TEXT);
+                assert_eq!(columnar_inv(&ciphertext, &key, transpose), SAMPLE_TEXT);
+            }
+        }
+    }
+}
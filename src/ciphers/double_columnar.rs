@@ -0,0 +1,166 @@
+use crate::cipher::{format_order, parse_permutation, task_rngs, AttackParams, CandidateHeap, Cipher, Setting};
+use crate::scoring::quadgram_score;
+use crate::{Candidate, CipherType};
+
+use super::columnar::{climb_order, order_fitness};
+use super::{columnar_enc, columnar_inv};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+
+pub struct DoubleColumnar;
+
+/// Keys of the first and second columnar transposition applied when encrypting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DoubleColumnarKey {
+    pub first: Vec<usize>,
+    pub second: Vec<usize>,
+    pub transpose: bool,
+}
+
+impl Cipher for DoubleColumnar {
+    type Key = DoubleColumnarKey;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::DoubleColumnar
+    }
+
+    fn id(&self) -> &'static str {
+        "double-columnar"
+    }
+
+    fn name(&self) -> &'static str {
+        "Double Columnar Transposition"
+    }
+
+    /// Two column orders separated by a slash, e.g. `2,0,1 / 3,1,0,2`.
+    fn parse_key(&self, key: &str, params: &AttackParams) -> Result<DoubleColumnarKey, String> {
+        let (first, second) = key.split_once('/')
+            .ok_or("expected two keys separated by `/`, e.g. `2,0,1 / 3,1,0,2`")?;
+        Ok(DoubleColumnarKey {
+            first: parse_permutation(first)?,
+            second: parse_permutation(second)?,
+            transpose: params.transpose,
+        })
+    }
+
+    fn encrypt(&self, key: &DoubleColumnarKey, plaintext: &str) -> String {
+        let once = columnar_enc(plaintext, &key.first, key.transpose);
+        columnar_enc(&once, &key.second, key.transpose)
+    }
+
+    fn decrypt(&self, key: &DoubleColumnarKey, ciphertext: &str) -> String {
+        let once = columnar_inv(ciphertext, &key.second, key.transpose);
+        columnar_inv(&once, &key.first, key.transpose)
+    }
+
+    fn settings(&self, _params: &AttackParams) -> Vec<Setting> {
+        vec![Setting::Transpose, Setting::MaxKeyLength("Max Key Length"), Setting::Restarts]
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        self.attack_with_rng(text, params, &mut rand::rng())
+    }
+}
+
+impl DoubleColumnar {
+    /// `attack` with the climbs drawn from `rng`.
+    fn attack_with_rng(&self, text: &str, params: &AttackParams, rng: &mut impl Rng) -> Vec<Candidate> {
+        let heap = CandidateHeap::new(3); // Keep only top 3 candidates
+
+        // Every pair of key lengths gets its own restarts; the keys themselves are climbed
+        // in turn instead of trying every pair of permutations
+        let lengths: Vec<(usize, usize)> = (2..=params.max_key_length)
+            .flat_map(|first| (2..=params.max_key_length).map(move |second| (first, second)))
+            .collect();
+        params.log(format_args!("Hill climbing {} pairs of key lengths", lengths.len()));
+
+        let tasks = task_rngs(rng, lengths.len());
+        lengths.into_par_iter().zip(tasks).for_each(|((first, second), mut rng)| {
+            for _ in 0..params.restarts.max(1) {
+                let key = self.hill_climb(text, first, second, params.transpose, &mut rng);
+                let decrypted_text = self.decrypt(&key, text);
+                let score = params.scorer.score(&decrypted_text);
+                heap.push(score, decrypted_text, key);
+            }
+        });
+
+        heap.into_candidates(|key, text| {
+            format!("Key: first {}, second {}\nText: {}", format_order(&key.first), format_order(&key.second), text)
+        })
+    }
+
+    /// Starts from random keys of the given lengths and climbs each key in turn with the
+    /// other held fixed, until a round of both climbs no longer improves the score.
+    fn hill_climb(
+        &self,
+        text: &str,
+        first: usize,
+        second: usize,
+        transpose: bool,
+        rng: &mut impl Rng,
+    ) -> DoubleColumnarKey {
+        let mut key = DoubleColumnarKey { first: (0..first).collect(), second: (0..second).collect(), transpose };
+        key.first.shuffle(rng);
+        key.second.shuffle(rng);
+
+        let mut best = f32::NEG_INFINITY;
+        loop {
+            let first = &key.first;
+            let (_, second) = climb_order(vec![key.second.clone()], |order| {
+                quadgram_score(&columnar_inv(&columnar_inv(text, order, transpose), first, transpose))
+            }, rng);
+            key.second = second;
+
+            // With the second key undone the rest is a single columnar transposition
+            let once = columnar_inv(text, &key.second, transpose);
+            let (score, first) = climb_order(vec![key.first.clone()], order_fitness(&once, transpose), rng);
+            key.first = first;
+
+            if score <= best {
+                return key;
+            }
+            best = score;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+    use crate::scoring::QuadgramScorer;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn parses_both_keys_and_undoes_them_in_reverse() {
+        let params = AttackParams { transpose: true, ..AttackParams::default() };
+        let key = DoubleColumnar.parse_key("3,0,4,1,2 / 2,5,0,3,1,4", &params).unwrap();
+        assert_eq!(key, DoubleColumnarKey { first: vec![3, 0, 4, 1, 2], second: vec![2, 5, 0, 3, 1, 4], transpose: true });
+        assert!(DoubleColumnar.parse_key("3,0,4,1,2", &params).is_err());
+
+        // Neither grid is full, and the second key is applied to the first's output
+        for transpose in [false, true] {
+            let key = DoubleColumnarKey { transpose, ..key.clone() };
+            let ciphertext = DoubleColumnar.encrypt(&key, SAMPLE_TEXT);
+            assert_eq!(ciphertext, columnar_enc(&columnar_enc(SAMPLE_TEXT, &key.first, transpose), &key.second, transpose));
+            assert_eq!(DoubleColumnar.decrypt(&key, &ciphertext), SAMPLE_TEXT);
+        }
+    }
+
+    #[test]
+    fn attack_recovers_both_keys() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let key = DoubleColumnarKey { first: vec![2, 0, 1], second: vec![1, 3, 0, 2], transpose: false };
+        let ciphertext = DoubleColumnar.encrypt(&key, SAMPLE_TEXT);
+        let params = AttackParams { max_key_length: 4, restarts: 2, scorer: Arc::new(QuadgramScorer), ..AttackParams::default() };
+
+        let candidates = DoubleColumnar.attack_with_rng(&ciphertext, &params, &mut StdRng::seed_from_u64(3260));
+        let (keys, text) = candidates[0].text.split_once('\n').unwrap();
+        assert!(keys.starts_with("Key: first [2, 0, 1] ") && keys.contains(", second [1, 3, 0, 2] "), "{}", keys);
+        assert_eq!(text, format!("Text: {}", SAMPLE_TEXT));
+    }
+}
@@ -0,0 +1,308 @@
+use crate::cipher::{AttackParams, CandidateHeap, Cipher, Setting};
+use crate::scoring::{chi_squared, indices_to_text, letter_indices};
+use crate::{Candidate, CipherType};
+
+use super::mod_inverse;
+
+use itertools::Itertools;
+
+// Matrix sizes tried by the attacks
+const HILL_SIZES: [usize; 2] = [2, 3];
+
+// Best rows kept for each position of a block in the ciphertext-only attack
+const HILL_TOP_ROWS: usize = 8;
+
+// Letter that pads the last block
+const X: usize = 23;
+
+pub struct Hill;
+
+/// An invertible `n`×`n` matrix mod 26, row-major. Each block of `n` plaintext letters is
+/// encrypted as the column vector `matrix · block`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HillKey {
+    n: usize,
+    matrix: Vec<usize>,
+    /// Inverse of `matrix` mod 26, used to decrypt
+    inverse: Vec<usize>,
+}
+
+impl HillKey {
+    /// Takes the `n`×`n` matrix row by row, values mod 26; fails unless the length is a
+    /// square and the matrix is invertible mod 26.
+    pub fn new(matrix: Vec<usize>) -> Result<Self, String> {
+        let n = (matrix.len() as f64).sqrt().round() as usize;
+        if n == 0 || n * n != matrix.len() {
+            return Err(format!("{} values do not make a square matrix", matrix.len()));
+        }
+        let matrix: Vec<usize> = matrix.into_iter().map(|value| value % 26).collect();
+        let inverse = matrix_inverse(&matrix, n).ok_or("the matrix is not invertible mod 26")?;
+        Ok(Self { n, matrix, inverse })
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// The matrix row by row.
+    pub fn matrix(&self) -> &[usize] {
+        &self.matrix
+    }
+}
+
+impl std::fmt::Display for HillKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}×{}", self.n, self.n)?;
+        for row in self.matrix.chunks(self.n) {
+            write!(f, "\n[{}]", row.iter().map(|value| format!("{:>2}", value)).join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Cipher for Hill {
+    type Key = HillKey;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Hill
+    }
+
+    fn id(&self) -> &'static str {
+        "hill"
+    }
+
+    fn name(&self) -> &'static str {
+        "Hill Cipher"
+    }
+
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<HillKey, String> {
+        parse_hill_key(key)
+    }
+
+    fn encrypt(&self, key: &HillKey, plaintext: &str) -> String {
+        indices_to_text(&multiply_blocks(&key.matrix, key.n, &letter_indices(plaintext)))
+    }
+
+    fn decrypt(&self, key: &HillKey, ciphertext: &str) -> String {
+        indices_to_text(&multiply_blocks(&key.inverse, key.n, &letter_indices(ciphertext)))
+    }
+
+    fn settings(&self, _params: &AttackParams) -> Vec<Setting> {
+        vec![Setting::Crib]
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        let ciphertext = letter_indices(text);
+        let heap = CandidateHeap::new(5);
+
+        for n in HILL_SIZES {
+            let keys = if params.crib.is_empty() {
+                ciphertext_only_keys(&ciphertext, n)
+            } else {
+                known_plaintext_keys(&ciphertext, &letter_indices(&params.crib), n)
+            };
+            params.log(format_args!("Testing {} {}×{} matrices", keys.len(), n, n));
+
+            for key in keys {
+                let decrypted_text = self.decrypt(&key, text);
+                let score = params.scorer.score(&decrypted_text);
+                heap.push(score, decrypted_text, key);
+            }
+        }
+
+        heap.into_candidates(|key, text| format!("Key: {}\nDecryption:\n{}", key, text))
+    }
+}
+
+/// Recovers the matrix from a crib found somewhere in the plaintext. Every position of the
+/// crib is tried; at each, `n` crib blocks aligned with the ciphertext blocks give
+/// `C = K · P`, so `K = C · P⁻¹` whenever the plaintext blocks are invertible.
+pub fn known_plaintext_keys(ciphertext: &[usize], crib: &[usize], n: usize) -> Vec<HillKey> {
+    let mut keys = Vec::new();
+    if crib.len() > ciphertext.len() {
+        return keys;
+    }
+    for position in 0..=ciphertext.len().saturating_sub(crib.len()) {
+        // Skip to the first block boundary inside the crib
+        let skip = (n - position % n) % n;
+        let start = position + skip;
+        let blocks = crib.len().saturating_sub(skip) / n;
+        if blocks < n {
+            continue;
+        }
+
+        // Any n of the crib blocks will do as long as they form an invertible matrix
+        for chosen in (0..blocks).combinations(n) {
+            // Blocks go in as columns
+            let column_matrix = |source: &[usize], offset: usize| -> Vec<usize> {
+                (0..n * n).map(|i| source[offset + chosen[i % n] * n + i / n]).collect()
+            };
+            let plain = column_matrix(crib, skip);
+            let Some(plain_inverse) = matrix_inverse(&plain, n) else {
+                continue;
+            };
+            let cipher = column_matrix(ciphertext, start);
+
+            if let Ok(key) = HillKey::new(matrix_multiply(&cipher, &plain_inverse, n)) {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+            break;
+        }
+    }
+    keys
+}
+
+/// Finds likely matrices without a crib. Row `i` of the decryption matrix alone decides
+/// letter `i` of every plaintext block, so each of the 26ⁿ possible rows is scored by how
+/// English the letters it produces look, and only combinations of the best rows are tried.
+pub fn ciphertext_only_keys(ciphertext: &[usize], n: usize) -> Vec<HillKey> {
+    let blocks: Vec<&[usize]> = ciphertext.chunks_exact(n).collect();
+    if blocks.is_empty() {
+        return Vec::new();
+    }
+
+    let mut rows: Vec<(f32, Vec<usize>)> = std::iter::repeat_n(0..26usize, n)
+        .multi_cartesian_product()
+        .map(|row| {
+            let mut counts = [0usize; 26];
+            for block in &blocks {
+                counts[row.iter().zip(block.iter()).map(|(&k, &c)| k * c).sum::<usize>() % 26] += 1;
+            }
+            (chi_squared(&counts), row)
+        })
+        .collect();
+    rows.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Each position of the block takes its plaintext from a different row
+    let best_rows: Vec<Vec<usize>> = rows.into_iter().take(HILL_TOP_ROWS).map(|(_, row)| row).collect();
+    std::iter::repeat_n(best_rows, n)
+        .multi_cartesian_product()
+        .filter_map(|chosen| {
+            let inverse = chosen.concat();
+            let matrix = matrix_inverse(&inverse, n)?;
+            Some(HillKey { n, matrix, inverse })
+        })
+        .collect()
+}
+
+/// Multiplies every block of `n` letters by the matrix, padding the last block with X.
+fn multiply_blocks(matrix: &[usize], n: usize, letters: &[usize]) -> Vec<usize> {
+    letters.chunks(n)
+        .flat_map(|block| {
+            let block: Vec<usize> = (0..n).map(|i| block.get(i).copied().unwrap_or(X)).collect();
+            matrix.chunks(n)
+                .map(|row| row.iter().zip(&block).map(|(a, b)| a * b).sum::<usize>() % 26)
+                .collect::<Vec<usize>>()
+        })
+        .collect()
+}
+
+fn matrix_multiply(a: &[usize], b: &[usize], n: usize) -> Vec<usize> {
+    (0..n * n)
+        .map(|i| (0..n).map(|k| a[i / n * n + k] * b[k * n + i % n]).sum::<usize>() % 26)
+        .collect()
+}
+
+/// Determinant mod 26 by cofactor expansion, which is fine for the sizes used here.
+fn determinant(matrix: &[usize], n: usize) -> usize {
+    if n == 1 {
+        return matrix[0] % 26;
+    }
+    (0..n).map(|col| {
+        let term = matrix[col] * determinant(&minor(matrix, n, 0, col), n - 1) % 26;
+        if col % 2 == 0 { term } else { 26 - term }
+    }).sum::<usize>() % 26
+}
+
+/// The matrix without row `skip_row` and column `skip_col`.
+fn minor(matrix: &[usize], n: usize, skip_row: usize, skip_col: usize) -> Vec<usize> {
+    (0..n * n)
+        .filter(|i| i / n != skip_row && i % n != skip_col)
+        .map(|i| matrix[i])
+        .collect()
+}
+
+/// Inverse mod 26 through the adjugate, if the determinant is coprime to 26.
+pub fn matrix_inverse(matrix: &[usize], n: usize) -> Option<Vec<usize>> {
+    let det_inverse = mod_inverse(determinant(matrix, n), 26)?;
+    if n == 1 {
+        return Some(vec![det_inverse]);
+    }
+
+    // The adjugate is the transpose of the cofactor matrix
+    Some((0..n * n).map(|i| {
+        let (row, col) = (i / n, i % n);
+        let cofactor = determinant(&minor(matrix, n, col, row), n - 1);
+        let cofactor = if (row + col) % 2 == 0 { cofactor } else { (26 - cofactor) % 26 };
+        cofactor * det_inverse % 26
+    }).collect())
+}
+
+/// Accepts 4 or 9 numbers (`3 3 2 5`) or letters (`hill`), read row by row.
+fn parse_hill_key(key: &str) -> Result<HillKey, String> {
+    let key = key.trim();
+    let values: Vec<usize> = if key.chars().all(|c| c.is_ascii_alphabetic()) {
+        letter_indices(key)
+    } else {
+        key.split([',', ' '])
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse::<usize>().map(|value| value % 26).map_err(|_| format!("`{}` is not a number", part)))
+            .collect::<Result<_, _>>()?
+    };
+
+    if values.len() != 4 && values.len() != 9 {
+        return Err(format!("expected 4 or 9 values for a 2×2 or 3×3 matrix, got {}", values.len()));
+    }
+    HillKey::new(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+
+    #[test]
+    fn rejects_singular_and_non_square_matrices() {
+        assert!(HillKey::new(vec![2, 4, 6, 8]).is_err());
+        assert!(HillKey::new(vec![3, 3, 2]).is_err());
+        assert!(HillKey::new(Vec::new()).is_err());
+        assert_eq!(HillKey::new(vec![29, 3, 2, 5]).unwrap().matrix(), [3, 3, 2, 5]);
+    }
+
+    #[test]
+    fn crib_longer_than_ciphertext_gives_no_keys() {
+        assert!(known_plaintext_keys(&letter_indices("abcd"), &letter_indices("aaaabcdf"), 2).is_empty());
+    }
+
+    #[test]
+    fn encrypts_textbook_examples_and_pads_with_x() {
+        let key = parse_hill_key("3 3 2 5").unwrap();
+        assert_eq!(Hill.encrypt(&key, "help"), "hiat");
+        assert_eq!(Hill.decrypt(&key, "hiat"), "help");
+        assert_eq!(Hill.decrypt(&key, &Hill.encrypt(&key, "hel")), "helx");
+
+        let key = parse_hill_key("gybnqkurp").unwrap();
+        assert_eq!(Hill.encrypt(&key, "act"), "poh");
+        assert_eq!(Hill.decrypt(&key, "poh"), "act");
+    }
+
+    #[test]
+    fn known_plaintext_recovers_a_3x3_matrix() {
+        let key = parse_hill_key("gybnqkurp").unwrap();
+        let ciphertext = letter_indices(&Hill.encrypt(&key, SAMPLE_TEXT));
+        // Starts one letter past a block boundary, so the first two letters are skipped
+        let crib = &letter_indices(SAMPLE_TEXT)[31..55];
+
+        assert!(known_plaintext_keys(&ciphertext, crib, 3).contains(&key));
+    }
+
+    #[test]
+    fn ciphertext_only_recovers_a_2x2_matrix() {
+        let key = parse_hill_key("3 3 2 5").unwrap();
+        let ciphertext = letter_indices(&Hill.encrypt(&key, SAMPLE_TEXT));
+
+        assert!(ciphertext_only_keys(&ciphertext, 2).contains(&key));
+    }
+}
@@ -0,0 +1,59 @@
+mod affine;
+mod amsco;
+mod atbash;
+mod autokey;
+mod beaufort;
+mod caesar;
+mod columnar;
+mod double_columnar;
+mod hill;
+mod myszkowski;
+mod periodic;
+mod playfair;
+mod railfence;
+mod route;
+mod substitution;
+mod vigenere;
+
+pub use affine::{mod_inverse, Affine, AffineKey};
+pub use amsco::{amsco_enc, amsco_inv, Amsco, AmscoKey};
+pub use atbash::Atbash;
+pub use autokey::Autokey;
+pub use beaufort::{beaufort_key_letter, Beaufort};
+pub use caesar::Caesar;
+pub use columnar::{columnar_enc, columnar_inv, Columnar, ColumnarKey, ColumnarSearch};
+pub use double_columnar::{DoubleColumnar, DoubleColumnarKey};
+pub use hill::{ciphertext_only_keys, known_plaintext_keys, matrix_inverse, Hill, HillKey};
+pub use myszkowski::{myszkowski_enc, myszkowski_inv, Myszkowski};
+pub use periodic::{periodic_enc, periodic_inv, Periodic};
+pub use playfair::{keysquare, playfair_prepare, Playfair};
+pub use railfence::{railfence_enc, railfence_inv, RailFence, RailKey, Redefence, RedefenceKey};
+pub use route::{route_enc, route_inv, Corner, Route, RouteCipher, RouteKey};
+pub use substitution::{solve_substitution, Substitution};
+pub use vigenere::{rank_column_shifts, Vigenere, AUTO_PERIOD_CANDIDATES, BEAM_SHIFTS, MAX_DICTIONARY_PAIRS};
+
+// About 600 letters of ordinary English with punctuation, shared by the cipher tests
+#[cfg(test)]
+pub(crate) const SAMPLE_TEXT: &str = "The harbour was quiet when the fishing boats came in that \
+    evening. Gulls circled above the nets, and the old men who mended them sat along the wall \
+    with their pipes, talking about the weather and the price of coal. Nobody noticed the \
+    stranger at first. He had walked down from the station with a small leather case, and he \
+    stood for a long time looking at the water as though he were trying to remember something. \
+    When the light began to fade he asked one of the men where he might find a room for the \
+    night. The man pointed up the hill towards the inn, and then, after a moment, he asked \
+    whether the stranger had been there before. The stranger smiled but did not answer, and \
+    he picked up his case and went slowly up the narrow street.";
+
+/// Whether `ciphertext` has a letter of the same case wherever `plaintext` has one, and
+/// every other character unchanged.
+#[cfg(test)]
+pub(crate) fn keeps_layout(plaintext: &str, ciphertext: &str) -> bool {
+    plaintext.chars().count() == ciphertext.chars().count()
+        && plaintext.chars().zip(ciphertext.chars()).all(|(p, c)| {
+            if p.is_ascii_alphabetic() {
+                c.is_ascii_alphabetic() && p.is_ascii_uppercase() == c.is_ascii_uppercase()
+            } else {
+                p == c
+            }
+        })
+}
@@ -0,0 +1,154 @@
+use crate::cipher::{dense_ranks, format_ranks, AttackParams, CandidateHeap, Cipher, Setting};
+use crate::{Candidate, CipherType};
+
+use super::columnar::{read_off, write_back};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+
+pub struct Myszkowski;
+
+impl Cipher for Myszkowski {
+    /// Reading rank of every column. Columns may share a rank, as repeated letters of a
+    /// keyword do; every rank from 0 up to the largest is used.
+    type Key = Vec<usize>;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Myszkowski
+    }
+
+    fn id(&self) -> &'static str {
+        "myszkowski"
+    }
+
+    fn name(&self) -> &'static str {
+        "Myszkowski Transposition"
+    }
+
+    /// A keyword such as `tomato`, or ranks such as `3,2,1,0,3,2`.
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<Vec<usize>, String> {
+        let key = key.trim();
+        let values: Vec<usize> = if key.chars().all(|c| c.is_ascii_alphabetic()) {
+            key.bytes().map(|b| b.to_ascii_lowercase() as usize).collect()
+        } else {
+            key.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|part| !part.is_empty())
+                .map(|part| part.parse::<usize>().map_err(|_| format!("`{}` is not a column number", part)))
+                .collect::<Result<_, _>>()?
+        };
+        if values.is_empty() {
+            return Err("the key is empty".to_owned());
+        }
+        Ok(dense_ranks(&values))
+    }
+
+    fn encrypt(&self, key: &Vec<usize>, plaintext: &str) -> String {
+        myszkowski_enc(plaintext, key)
+    }
+
+    fn decrypt(&self, key: &Vec<usize>, ciphertext: &str) -> String {
+        myszkowski_inv(ciphertext, key)
+    }
+
+    fn settings(&self, _params: &AttackParams) -> Vec<Setting> {
+        vec![Setting::MaxKeyLength("Max Key Length")]
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        let heap = CandidateHeap::new(3); // Keep only top 3 candidates
+
+        // Every ranking of the columns, ties included: a grouping of the columns into
+        // blocks, then every order of the blocks
+        (1..=params.max_key_length).into_par_iter().for_each(|key_length| {
+            for grouping in set_partitions(key_length) {
+                let blocks = grouping.iter().max().map_or(0, |&max| max + 1);
+                for block_order in (0..blocks).permutations(blocks) {
+                    let key: Vec<usize> = grouping.iter().map(|&block| block_order[block]).collect();
+                    let decrypted_text = myszkowski_inv(text, &key);
+                    let score = params.scorer.score(&decrypted_text);
+                    heap.push(score, decrypted_text, key);
+                }
+            }
+        });
+
+        heap.into_candidates(|key, text| format!("Key: {}\nText: {}", format_ranks(key), text))
+    }
+}
+
+/// Every way to group `n` columns, as the block of each column numbered in order of
+/// first appearance (restricted growth strings).
+fn set_partitions(n: usize) -> Vec<Vec<usize>> {
+    let mut partitions = vec![Vec::new()];
+    for _ in 0..n {
+        partitions = partitions.into_iter().flat_map(|partial: Vec<usize>| {
+            let next_block = partial.iter().max().map_or(0, |&max| max + 1);
+            (0..=next_block).map(move |block| {
+                let mut extended = partial.clone();
+                extended.push(block);
+                extended
+            })
+        }).collect();
+    }
+    partitions
+}
+
+/// Plaintext positions in reading order: ranks in turn, and within a rank the tied columns
+/// are read across, row by row.
+fn reading_positions(n: usize, key: &[usize]) -> Vec<usize> {
+    let width = key.len();
+    let rows = n.div_ceil(width);
+    let ranks = key.iter().max().map_or(0, |&max| max + 1);
+
+    (0..ranks)
+        .flat_map(|rank| {
+            (0..rows).flat_map(move |row| {
+                (0..width).filter(move |&col| key[col] == rank).map(move |col| row * width + col)
+            })
+        })
+        .filter(|&i| i < n)
+        .collect()
+}
+
+pub fn myszkowski_enc(text: &str, key: &[usize]) -> String {
+    read_off(text, reading_positions(text.chars().count(), key))
+}
+
+pub fn myszkowski_inv(text: &str, key: &[usize]) -> String {
+    write_back(text, reading_positions(text.chars().count(), key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+    use crate::scoring::QuadgramScorer;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn tied_columns_are_read_across_each_row() {
+        let key = Myszkowski.parse_key("tomato", &AttackParams::default()).unwrap();
+        assert_eq!(key, [3, 2, 1, 0, 3, 2]);
+        assert_eq!(Myszkowski.encrypt(&key, "WEAREDISCOVEREDFLEEATONCE"), "ROFOACDTEDSEEEACWEIVRLENE");
+        assert_eq!(Myszkowski.decrypt(&key, "ROFOACDTEDSEEEACWEIVRLENE"), "WEAREDISCOVEREDFLEEATONCE");
+    }
+
+    #[test]
+    fn set_partitions_count_every_grouping_once() {
+        // Bell numbers
+        let counts: Vec<usize> = (1..=5).map(|n| set_partitions(n).len()).collect();
+        assert_eq!(counts, [1, 2, 5, 15, 52]);
+        assert_eq!(set_partitions(3), [[0, 0, 0], [0, 0, 1], [0, 1, 0], [0, 1, 1], [0, 1, 2]]);
+    }
+
+    #[test]
+    fn attack_recovers_a_key_with_ties() {
+        let ciphertext = myszkowski_enc(SAMPLE_TEXT, &[2, 0, 2, 1, 0]);
+        let params = AttackParams { max_key_length: 5, scorer: Arc::new(QuadgramScorer), ..AttackParams::default() };
+
+        let candidates = Myszkowski.attack(&ciphertext, &params);
+        let (key, text) = candidates[0].text.split_once('\n').unwrap();
+        assert!(key.starts_with("Key: [2, 0, 2, 1, 0]"), "{}", key);
+        assert_eq!(text, format!("Text: {}", SAMPLE_TEXT));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::cipher::{format_order, parse_permutation, AttackParams, CandidateHeap, Cipher, Setting};
+use crate::{Candidate, CipherType};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+
+pub struct Periodic;
+
+impl Cipher for Periodic {
+    type Key = Vec<usize>;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Periodic
+    }
+
+    fn id(&self) -> &'static str {
+        "periodic"
+    }
+
+    fn name(&self) -> &'static str {
+        "Periodic Transposition"
+    }
+
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<Vec<usize>, String> {
+        parse_permutation(key)
+    }
+
+    fn encrypt(&self, key: &Vec<usize>, plaintext: &str) -> String {
+        periodic_enc(plaintext, key)
+    }
+
+    fn decrypt(&self, key: &Vec<usize>, ciphertext: &str) -> String {
+        periodic_inv(ciphertext, key)
+    }
+
+    fn settings(&self, params: &AttackParams) -> Vec<Setting> {
+        let mut settings = vec![Setting::Period, Setting::CheckAllPeriods];
+        if params.check_all_periods {
+            settings.push(Setting::MaxKeyLength("Max Period"));
+        }
+        settings
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        let heap = CandidateHeap::new(3); // Keep only top 3 candidates
+        let period = params.period;
+        let periods_to_check = if params.check_all_periods {
+            (period..=params.max_key_length).collect::<Vec<usize>>()
+        } else {
+            vec![period]
+        };
+
+        // Process each period in parallel
+        periods_to_check.into_par_iter().for_each(|current_period| {
+            let permutations = (0..current_period).permutations(current_period);
+
+            // Process permutations for this period
+            for permutation in permutations {
+                let decrypted_text = periodic_inv(text, &permutation);
+                let score = params.scorer.score(&decrypted_text);
+                heap.push(score, decrypted_text, permutation);
+            }
+        });
+
+        heap.into_candidates(|key, text| format!("Key: {}\nText: {}", format_order(key), text))
+    }
+}
+
+pub fn periodic_enc(text: &str, key: &[usize]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = Vec::new();
+    let period = key.len();
+
+    for chunk in chars.chunks(period) {
+        if chunk.len() == period {
+            // Position i of each block takes the letter from position key[i]
+            result.extend(key.iter().map(|&pos| chunk[pos]));
+        } else {
+            // Partial chunks at the end are left as is
+            result.extend(chunk);
+        }
+    }
+
+    result.into_iter().collect()
+}
+
+pub fn periodic_inv(text: &str, key: &[usize]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = Vec::new();
+    let period = key.len();
+
+    // Process the text in chunks of size period
+    for chunk in chars.chunks(period) {
+        if chunk.len() == period {
+            // Only apply permutation to complete chunks
+            let mut chunk_vec = chunk.to_vec();
+            // Apply the inverse permutation
+            for (i, &pos) in key.iter().enumerate() {
+                chunk_vec[pos] = chunk[i];
+            }
+            result.extend(chunk_vec);
+        } else {
+            // For partial chunks at the end, just add them as is
+            result.extend(chunk);
+        }
+    }
+
+    result.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+
+    #[test]
+    fn partial_final_block_is_left_in_place() {
+        assert_eq!(periodic_enc("ABCDEFGH", &[2, 0, 1]), "CABFDEGH");
+        assert_eq!(periodic_inv("CABFDEGH", &[2, 0, 1]), "ABCDEFGH");
+    }
+
+    #[test]
+    fn round_trip_with_a_partial_final_block() {
+        for key in [vec![2, 0, 1, 4, 3], vec![5, 3, 0, 1, 4, 2]] {
+            assert!(!SAMPLE_TEXT.len().is_multiple_of(key.len()));
+            let ciphertext = periodic_enc(SAMPLE_TEXT, &key);
+            assert_ne!(ciphertext, SAMPLE_TEXT);
+            assert_eq!(periodic_inv(&ciphertext, &key), SAMPLE_TEXT);
+        }
+    }
+}
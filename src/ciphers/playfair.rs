@@ -0,0 +1,319 @@
+use crate::cipher::{task_rngs, AttackParams, CandidateHeap, Cipher, Setting};
+use crate::scoring::{indices_to_text, letter_indices, quadgram_score_indices};
+use crate::{Candidate, CipherType};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+
+// Simulated annealing schedule: the temperature falls from ANNEAL_START_TEMP towards zero
+// in ANNEAL_LEVELS equal steps, trying ANNEAL_STEPS keysquares at each temperature
+const ANNEAL_START_TEMP: f32 = 10.0;
+const ANNEAL_LEVELS: usize = 50;
+const ANNEAL_STEPS: usize = 10_000;
+
+// J is merged into I, so the square holds the other 25 letters
+const J: u8 = 9;
+const I: u8 = 8;
+const X: u8 = 23;
+const Q: u8 = 16;
+
+pub struct Playfair;
+
+impl Cipher for Playfair {
+    /// The 5×5 square read row by row, as alphabet positions without J.
+    type Key = [u8; 25];
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Playfair
+    }
+
+    fn id(&self) -> &'static str {
+        "playfair"
+    }
+
+    fn name(&self) -> &'static str {
+        "Playfair Cipher"
+    }
+
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<[u8; 25], String> {
+        if !key.chars().any(|c| c.is_ascii_alphabetic()) {
+            return Err("the key has no letters".to_owned());
+        }
+        Ok(keysquare(key))
+    }
+
+    fn encrypt(&self, key: &[u8; 25], plaintext: &str) -> String {
+        let digraphs = playfair_digraphs(&playfair_prepare(plaintext));
+        indices_to_text(&apply(&Square::new(key), &digraphs, 1))
+    }
+
+    fn decrypt(&self, key: &[u8; 25], ciphertext: &str) -> String {
+        let digraphs = playfair_digraphs(&indices_to_text(&letters(ciphertext)));
+        indices_to_text(&apply(&Square::new(key), &digraphs, 4))
+    }
+
+    fn settings(&self, _params: &AttackParams) -> Vec<Setting> {
+        vec![Setting::Restarts]
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        self.attack_with_rng(text, params, &mut rand::rng())
+    }
+}
+
+impl Playfair {
+    /// `attack` with the restarts drawn from `rng`.
+    fn attack_with_rng(&self, text: &str, params: &AttackParams, rng: &mut impl Rng) -> Vec<Candidate> {
+        let digraphs = playfair_digraphs(&indices_to_text(&letters(text)));
+        if digraphs.is_empty() {
+            return Vec::new();
+        }
+
+        let heap = CandidateHeap::new(5);
+        task_rngs(rng, params.restarts.max(1)).into_par_iter().for_each(|mut rng| {
+            let key = anneal(&digraphs, &mut rng);
+            let decrypted_text = self.decrypt(&key, text);
+            let score = params.scorer.score(&decrypted_text);
+            heap.push(score, decrypted_text, key);
+        });
+
+        heap.into_candidates(|key, text| format!("Key:\n{}\nDecryption:\n{}", square_to_string(key), text))
+    }
+}
+
+/// Letter positions of a keysquare, for looking up rows and columns.
+struct Square {
+    letters: [u8; 25],
+    position: [usize; 26],
+}
+
+impl Square {
+    fn new(letters: &[u8; 25]) -> Self {
+        let mut position = [0; 26];
+        for (i, &letter) in letters.iter().enumerate() {
+            position[letter as usize] = i;
+        }
+        position[J as usize] = position[I as usize];
+        Square { letters: *letters, position }
+    }
+
+    /// Enciphers a digraph with `shift` 1, deciphers it with `shift` 4.
+    fn digraph(&self, a: u8, b: u8, shift: usize) -> (u8, u8) {
+        let (ra, ca) = (self.position[a as usize] / 5, self.position[a as usize] % 5);
+        let (rb, cb) = (self.position[b as usize] / 5, self.position[b as usize] % 5);
+        let at = |row: usize, col: usize| self.letters[row * 5 + col];
+
+        if ra == rb {
+            (at(ra, (ca + shift) % 5), at(rb, (cb + shift) % 5))
+        } else if ca == cb {
+            (at((ra + shift) % 5, ca), at((rb + shift) % 5, cb))
+        } else {
+            (at(ra, cb), at(rb, ca))
+        }
+    }
+}
+
+fn apply(square: &Square, digraphs: &[(u8, u8)], shift: usize) -> Vec<u8> {
+    digraphs.iter()
+        .flat_map(|&(a, b)| {
+            let (a, b) = square.digraph(a, b, shift);
+            [a, b]
+        })
+        .collect()
+}
+
+/// Builds a keysquare from a keyword: its letters in order without repeats, then the rest
+/// of the alphabet. J counts as I.
+pub fn keysquare(keyword: &str) -> [u8; 25] {
+    let mut square = Vec::with_capacity(25);
+    for letter in letters(keyword).into_iter().chain(0..26) {
+        if letter != J && !square.contains(&letter) {
+            square.push(letter);
+        }
+    }
+    square.try_into().expect("25 letters once J is dropped")
+}
+
+/// Prepares plaintext for Playfair: drops everything but letters, turns J into I, splits
+/// doubled letters within a digraph with an X (a Q if the letter is X) and pads an odd
+/// length the same way.
+pub fn playfair_prepare(text: &str) -> String {
+    let letters = letters(text);
+    let filler = |letter: u8| if letter == X { Q } else { X };
+
+    let mut prepared = Vec::with_capacity(letters.len() + letters.len() / 2);
+    let mut i = 0;
+    while i < letters.len() {
+        let a = letters[i];
+        match letters.get(i + 1) {
+            Some(&b) if b != a => {
+                prepared.extend([a, b]);
+                i += 2;
+            }
+            _ => {
+                prepared.extend([a, filler(a)]);
+                i += 1;
+            }
+        }
+    }
+    indices_to_text(&prepared)
+}
+
+/// Splits prepared text into digraphs, padding an odd length with X.
+fn playfair_digraphs(text: &str) -> Vec<(u8, u8)> {
+    letters(text).chunks(2)
+        .map(|pair| (pair[0], pair.get(1).copied().unwrap_or(X)))
+        .collect()
+}
+
+/// Lowercase alphabet positions of the letters in the text, with J turned into I.
+fn letters(text: &str) -> Vec<u8> {
+    letter_indices(text).into_iter()
+        .map(|letter| match letter as u8 {
+            J => I,
+            letter => letter,
+        })
+        .collect()
+}
+
+/// The keysquare as five rows of five uppercase letters.
+fn square_to_string(key: &[u8; 25]) -> String {
+    key.chunks(5)
+        .map(|row| row.iter().map(|&letter| ((letter + b'A') as char).to_string()).collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Simulated annealing over keysquares from a random start, returning the best square seen.
+fn anneal(digraphs: &[(u8, u8)], rng: &mut impl Rng) -> [u8; 25] {
+    let mut plaintext = vec![0; digraphs.len() * 2];
+
+    // Total rather than mean log probability, so the temperature does not depend on length
+    let mut fitness = |key: &[u8; 25]| {
+        let square = Square::new(key);
+        for (i, &(a, b)) in digraphs.iter().enumerate() {
+            let (a, b) = square.digraph(a, b, 4);
+            plaintext[2 * i] = a as usize;
+            plaintext[2 * i + 1] = b as usize;
+        }
+        quadgram_score_indices(&plaintext) * plaintext.len().saturating_sub(3) as f32
+    };
+
+    let mut key = keysquare("");
+    key.shuffle(rng);
+    let mut current = fitness(&key);
+    let (mut best_key, mut best) = (key, current);
+
+    // Each level's temperature is computed from its index so rounding cannot add or drop one
+    for level in 0..ANNEAL_LEVELS {
+        let temp = ANNEAL_START_TEMP * (ANNEAL_LEVELS - level) as f32 / ANNEAL_LEVELS as f32;
+        for _ in 0..ANNEAL_STEPS {
+            let child = modify(&key, rng);
+            let score = fitness(&child);
+            let delta = score - current;
+            if delta >= 0.0 || rng.random::<f32>() < (delta / temp).exp() {
+                key = child;
+                current = score;
+                if current > best {
+                    best_key = key;
+                    best = current;
+                }
+            }
+        }
+    }
+    best_key
+}
+
+/// A random small change to a keysquare: usually a swap of two letters, sometimes a
+/// rearrangement of whole rows or columns.
+fn modify(key: &[u8; 25], rng: &mut impl Rng) -> [u8; 25] {
+    let mut child = *key;
+    let (a, b) = (rng.random_range(0..5), rng.random_range(0..5));
+    match rng.random_range(0..50) {
+        0 => {
+            for col in 0..5 {
+                child.swap(a * 5 + col, b * 5 + col);
+            }
+        }
+        1 => {
+            for row in 0..5 {
+                child.swap(row * 5 + a, row * 5 + b);
+            }
+        }
+        2 => child.chunks_mut(5).for_each(|row| row.reverse()),
+        3 => {
+            for row in 0..2 {
+                for col in 0..5 {
+                    child.swap(row * 5 + col, (4 - row) * 5 + col);
+                }
+            }
+        }
+        4 => child.reverse(),
+        // Moving a row or column elsewhere shifts the others by one, which would otherwise
+        // take several swaps that each lower the score
+        5 if a < b => child[a * 5..b * 5 + 5].rotate_left(5),
+        5 => child[b * 5..a * 5 + 5].rotate_right(5),
+        6 => {
+            for row in child.chunks_mut(5) {
+                if a < b {
+                    row[a..=b].rotate_left(1);
+                } else {
+                    row[b..=a].rotate_right(1);
+                }
+            }
+        }
+        _ => child.swap(rng.random_range(0..25), rng.random_range(0..25)),
+    }
+    child
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+    use crate::scoring::QuadgramScorer;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn preparation_splits_doubled_letters_and_pads() {
+        assert_eq!(playfair_prepare("Hide the gold in the tree stump"), "hidethegoldinthetrexestump");
+        assert_eq!(playfair_prepare("balloon"), "balxloon");
+        assert_eq!(playfair_prepare("taxxi"), "taxqxi");
+        assert_eq!(playfair_prepare("fox"), "foxq");
+        assert_eq!(playfair_prepare("jam"), "iamx");
+    }
+
+    #[test]
+    fn decryption_gives_the_prepared_plaintext() {
+        let key = keysquare("playfair example");
+        let ciphertext = Playfair.encrypt(&key, "Hide the gold in the tree stump");
+        assert_eq!(ciphertext, "bmodzbxdnabekudmuixmmouvif");
+        assert_eq!(Playfair.decrypt(&key, &ciphertext), "hidethegoldinthetrexestump");
+    }
+
+    #[test]
+    fn restarts_recover_the_plaintext() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let ciphertext = Playfair.encrypt(&keysquare("monarchy"), SAMPLE_TEXT);
+        let params = AttackParams { restarts: 3, scorer: Arc::new(QuadgramScorer), ..AttackParams::default() };
+
+        // Rotating the rows or columns of a square gives the same cipher, so compare the text
+        let candidates = Playfair.attack_with_rng(&ciphertext, &params, &mut StdRng::seed_from_u64(3260));
+        assert!(candidates[0].text.ends_with(&format!("\nDecryption:\n{}", playfair_prepare(SAMPLE_TEXT))));
+    }
+
+    #[test]
+    fn attack_reports_the_square_and_decryption() {
+        let ciphertext = Playfair.encrypt(&keysquare("monarchy"), "Meet me at the old mill at noon");
+        let params = AttackParams { restarts: 1, scorer: Arc::new(QuadgramScorer), ..AttackParams::default() };
+
+        let candidates = Playfair.attack(&ciphertext, &params);
+        let text = &candidates[0].text;
+        assert!(text.starts_with("Key:\n") && text.lines().nth(5).unwrap().len() == 9, "{}", text);
+        assert_eq!(text.lines().nth(6), Some("Decryption:"));
+        assert!(Playfair.attack("123", &params).is_empty());
+    }
+}
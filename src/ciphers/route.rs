@@ -0,0 +1,262 @@
+use crate::cipher::{AttackParams, CandidateHeap, Cipher};
+use crate::{compute_factors, Candidate, CipherType};
+
+use super::columnar::{read_off, write_back};
+
+use rayon::prelude::*;
+
+/// Writes the text into a grid row by row and reads it off along a route. The attack tries
+/// the widths from `compute_factors` and, since the last row may be short, every other
+/// width too.
+pub struct RouteCipher;
+
+/// Path taken through the grid, described from the top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Route {
+    Rows,
+    Columns,
+    /// Rows, alternating left-to-right and right-to-left
+    Snake,
+    /// Columns, alternating downwards and upwards
+    SnakeColumns,
+    /// Anti-diagonals, each read from its top-right end
+    Diagonals,
+    SpiralClockwise,
+    SpiralCounterclockwise,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+const ROUTES: [(Route, &str); 7] = [
+    (Route::Rows, "rows"),
+    (Route::Columns, "columns"),
+    (Route::Snake, "snake"),
+    (Route::SnakeColumns, "snake-columns"),
+    (Route::Diagonals, "diagonal"),
+    (Route::SpiralClockwise, "spiral"),
+    (Route::SpiralCounterclockwise, "spiral-ccw"),
+];
+
+const CORNERS: [(Corner, &str); 4] = [
+    (Corner::TopLeft, "top-left"),
+    (Corner::TopRight, "top-right"),
+    (Corner::BottomLeft, "bottom-left"),
+    (Corner::BottomRight, "bottom-right"),
+];
+
+/// The plaintext is written row by row into a grid `cols` wide, then read along `route`
+/// starting from `corner`. The number of rows follows from the text length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RouteKey {
+    pub cols: usize,
+    pub route: Route,
+    pub corner: Corner,
+}
+
+impl std::fmt::Display for RouteKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let route = ROUTES.iter().find(|(route, _)| *route == self.route).unwrap().1;
+        let corner = CORNERS.iter().find(|(corner, _)| *corner == self.corner).unwrap().1;
+        write!(f, "{} {} {}", self.cols, route, corner)
+    }
+}
+
+impl Cipher for RouteCipher {
+    type Key = RouteKey;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Route
+    }
+
+    fn id(&self) -> &'static str {
+        "route"
+    }
+
+    fn name(&self) -> &'static str {
+        "Route Transposition"
+    }
+
+    /// `<columns> <route> [corner]`, e.g. `5 spiral bottom-right`.
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<RouteKey, String> {
+        let parts: Vec<&str> = key.split_whitespace().collect();
+        let (cols, route, corner) = match parts[..] {
+            [cols, route] => (cols, route, "top-left"),
+            [cols, route, corner] => (cols, route, corner),
+            _ => return Err("expected `<columns> <route> [corner]`, e.g. `5 spiral bottom-right`".to_owned()),
+        };
+
+        let cols = match cols.parse::<usize>() {
+            Ok(cols) if cols > 0 => cols,
+            _ => return Err(format!("`{}` is not a number of columns", cols)),
+        };
+        let route = ROUTES.iter().find(|(_, name)| *name == route).map(|&(route, _)| route)
+            .ok_or_else(|| format!("unknown route `{}` (expected one of: {})", route,
+                ROUTES.iter().map(|(_, name)| *name).collect::<Vec<_>>().join(", ")))?;
+        let corner = CORNERS.iter().find(|(_, name)| *name == corner).map(|&(corner, _)| corner)
+            .ok_or_else(|| format!("unknown corner `{}` (expected one of: {})", corner,
+                CORNERS.iter().map(|(_, name)| *name).collect::<Vec<_>>().join(", ")))?;
+        Ok(RouteKey { cols, route, corner })
+    }
+
+    fn encrypt(&self, key: &RouteKey, plaintext: &str) -> String {
+        route_enc(plaintext, key)
+    }
+
+    fn decrypt(&self, key: &RouteKey, ciphertext: &str) -> String {
+        route_inv(ciphertext, key)
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        let heap = CandidateHeap::new(3); // Keep only top 3 candidates
+
+        // The factors of the length give every full grid. A route cipher may also leave the
+        // last row short, which no factor describes, so every other width leaving at least
+        // two full rows is tried as well. Each grid is read along every route from
+        // every corner
+        let n = text.chars().count();
+        let factors = compute_factors(n);
+        params.log(format_args!("Factors: {:?}", factors));
+        let ragged = (2..=n / 2).filter(|cols| !factors.contains(cols));
+        let widths: Vec<usize> = factors.iter().copied().chain(ragged).collect();
+        params.log(format_args!("Trying {} grid widths, {} with a short last row",
+            widths.len(), widths.len() - factors.len()));
+        widths.into_par_iter().for_each(|cols| {
+            for &(route, _) in &ROUTES {
+                for &(corner, _) in &CORNERS {
+                    let key = RouteKey { cols, route, corner };
+                    let decrypted_text = route_inv(text, &key);
+                    let score = params.scorer.score(&decrypted_text);
+                    heap.push(score, decrypted_text, key);
+                }
+            }
+        });
+
+        let rows = |cols: usize| text.chars().count().div_ceil(cols);
+        heap.into_candidates(|key, text| format!("Key: {} ({}×{} grid)\nText: {}", key, rows(key.cols), key.cols, text))
+    }
+}
+
+/// Cells of a `rows`×`cols` grid in route order, starting from the top-left.
+fn route_cells(rows: usize, cols: usize, route: Route) -> Vec<(usize, usize)> {
+    match route {
+        Route::Rows => (0..rows).flat_map(|r| (0..cols).map(move |c| (r, c))).collect(),
+        Route::Columns => (0..cols).flat_map(|c| (0..rows).map(move |r| (r, c))).collect(),
+        Route::Snake => (0..rows).flat_map(|r| {
+            (0..cols).map(move |c| (r, if r % 2 == 0 { c } else { cols - 1 - c }))
+        }).collect(),
+        Route::SnakeColumns => (0..cols).flat_map(|c| {
+            (0..rows).map(move |r| (if c % 2 == 0 { r } else { rows - 1 - r }, c))
+        }).collect(),
+        Route::Diagonals => (0..rows + cols - 1).flat_map(|d| {
+            (0..rows).filter(move |&r| d >= r && d - r < cols).map(move |r| (r, d - r))
+        }).collect(),
+        Route::SpiralClockwise => spiral(rows, cols),
+        // The same spiral with rows and columns swapped turns the other way
+        Route::SpiralCounterclockwise => spiral(cols, rows).into_iter().map(|(r, c)| (c, r)).collect(),
+    }
+}
+
+/// Clockwise spiral inwards from the top-left corner.
+fn spiral(rows: usize, cols: usize) -> Vec<(usize, usize)> {
+    let mut cells = Vec::with_capacity(rows * cols);
+    let (mut top, mut bottom, mut left, mut right) = (0, rows, 0, cols);
+    while top < bottom && left < right {
+        cells.extend((left..right).map(|c| (top, c)));
+        cells.extend((top + 1..bottom).map(|r| (r, right - 1)));
+        if bottom - top > 1 {
+            cells.extend((left..right - 1).rev().map(|c| (bottom - 1, c)));
+        }
+        if right - left > 1 {
+            cells.extend((top + 1..bottom - 1).rev().map(|r| (r, left)));
+        }
+        top += 1;
+        bottom -= 1;
+        left += 1;
+        right -= 1;
+    }
+    cells
+}
+
+/// Plaintext positions in the order the route reads them. A short last row leaves empty
+/// cells, which are skipped.
+fn reading_positions(n: usize, key: &RouteKey) -> Vec<usize> {
+    let cols = key.cols;
+    let rows = n.div_ceil(cols);
+    route_cells(rows, cols, key.route)
+        .into_iter()
+        .map(|(r, c)| match key.corner {
+            Corner::TopLeft => (r, c),
+            Corner::TopRight => (r, cols - 1 - c),
+            Corner::BottomLeft => (rows - 1 - r, c),
+            Corner::BottomRight => (rows - 1 - r, cols - 1 - c),
+        })
+        .map(|(r, c)| r * cols + c)
+        .filter(|&i| i < n)
+        .collect()
+}
+
+pub fn route_enc(text: &str, key: &RouteKey) -> String {
+    read_off(text, reading_positions(text.chars().count(), key))
+}
+
+pub fn route_inv(text: &str, key: &RouteKey) -> String {
+    write_back(text, reading_positions(text.chars().count(), key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+    use crate::scoring::{indices_to_text, letter_indices, QuadgramScorer};
+
+    use std::sync::Arc;
+
+    #[test]
+    fn routes_skip_the_empty_cells_of_a_ragged_grid() {
+        // ABC / DEF / GH
+        let key = RouteKey { cols: 3, route: Route::Columns, corner: Corner::TopLeft };
+        assert_eq!(route_enc("ABCDEFGH", &key), "ADGBEHCF");
+        let key = RouteKey { cols: 3, route: Route::SpiralClockwise, corner: Corner::TopLeft };
+        assert_eq!(route_enc("ABCDEFGH", &key), "ABCFHGDE");
+
+        for cols in [5, 7, 9] {
+            assert!(!SAMPLE_TEXT.len().is_multiple_of(cols));
+            for (route, _) in ROUTES {
+                for (corner, _) in CORNERS {
+                    let key = RouteKey { cols, route, corner };
+                    let ciphertext = route_enc(SAMPLE_TEXT, &key);
+                    assert_eq!(route_inv(&ciphertext, &key), SAMPLE_TEXT, "{}", key);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn keys_print_the_way_they_are_parsed() {
+        let params = AttackParams::default();
+        let key = RouteCipher.parse_key("5 spiral-ccw bottom-right", &params).unwrap();
+        assert_eq!(key, RouteKey { cols: 5, route: Route::SpiralCounterclockwise, corner: Corner::BottomRight });
+        assert_eq!(key.to_string(), "5 spiral-ccw bottom-right");
+        assert_eq!(RouteCipher.parse_key("5 snake", &params).unwrap().corner, Corner::TopLeft);
+        assert!(RouteCipher.parse_key("0 rows", &params).is_err());
+        assert!(RouteCipher.parse_key("5 zigzag", &params).is_err());
+    }
+
+    #[test]
+    fn solves_a_grid_with_a_short_last_row() {
+        let plaintext = indices_to_text(&letter_indices(SAMPLE_TEXT));
+        let key = RouteCipher.parse_key("19 spiral top-left", &AttackParams::default()).unwrap();
+        assert!(!plaintext.len().is_multiple_of(19));
+        let ciphertext = route_enc(&plaintext, &key);
+
+        let params = AttackParams { scorer: Arc::new(QuadgramScorer), ..AttackParams::default() };
+        let candidates = RouteCipher.attack(&ciphertext, &params);
+        assert!(candidates[0].text.ends_with(&plaintext));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::cipher::{task_rngs, AttackParams, CandidateHeap, Cipher, Setting};
+use crate::scoring::{letter_indices, quadgram_score_indices};
+use crate::{Candidate, CipherType};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+
+// A climb ends after this many random swaps in a row fail to improve the score
+const MAX_STALE_SWAPS: usize = 2000;
+
+// Each restart climbs again KICKS times from its best key with KICK_SWAPS random swaps made
+const KICKS: usize = 5;
+const KICK_SWAPS: usize = 3;
+
+pub struct Substitution;
+
+impl Cipher for Substitution {
+    /// The cipher alphabet: plaintext letter `i` encrypts to `key[i]` (both 0-25).
+    type Key = [u8; 26];
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Substitution
+    }
+
+    fn id(&self) -> &'static str {
+        "substitution"
+    }
+
+    fn name(&self) -> &'static str {
+        "Simple Substitution"
+    }
+
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<[u8; 26], String> {
+        parse_alphabet(key)
+    }
+
+    fn encrypt(&self, key: &[u8; 26], plaintext: &str) -> String {
+        substitute(plaintext, key)
+    }
+
+    fn decrypt(&self, key: &[u8; 26], ciphertext: &str) -> String {
+        substitute(ciphertext, &invert(key))
+    }
+
+    fn settings(&self, _params: &AttackParams) -> Vec<Setting> {
+        vec![Setting::Restarts, Setting::Workbench]
+    }
+
+    fn attack_with_alphabets(&self, text: &str, params: &AttackParams) -> Option<Vec<(Candidate, [u8; 26])>> {
+        Some(self.attack_with_keys(text, params))
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        self.attack_with_keys(text, params).into_iter().map(|(candidate, _)| candidate).collect()
+    }
+}
+
+impl Substitution {
+    /// The same candidates as `attack`, each with the cipher alphabet that produced it.
+    pub fn attack_with_keys(&self, text: &str, params: &AttackParams) -> Vec<(Candidate, [u8; 26])> {
+        let heap = CandidateHeap::new(5);
+        let keys = solve_substitution(text, params.restarts, &mut rand::rng());
+        params.log(format_args!("Found {} distinct keys over {} restarts", keys.len(), params.restarts.max(1)));
+        for key in keys {
+            let decrypted_text = self.decrypt(&key, text);
+            let score = params.scorer.score(&decrypted_text);
+            heap.push(score, decrypted_text, key);
+        }
+
+        heap.into_keyed_candidates(|key, text| format!(
+            "Plain: abcdefghijklmnopqrstuvwxyz\nKey:   {}\nDecryption:\n{}",
+            alphabet_to_string(key), text))
+    }
+}
+
+/// Hill-climbs from `restarts` random keys drawn from `rng` in parallel and returns the
+/// distinct cipher alphabets found, best quadgram score first.
+pub fn solve_substitution(text: &str, restarts: usize, rng: &mut impl Rng) -> Vec<[u8; 26]> {
+    let ciphertext = letter_indices(text);
+
+    // Every restart climbs from its own random key
+    let mut keys: Vec<(f32, [u8; 26])> = task_rngs(rng, restarts.max(1)).into_par_iter()
+        .map(|mut rng| hill_climb(&ciphertext, &mut rng))
+        .collect();
+    keys.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    // Restarts often reach the same key
+    let mut distinct: Vec<[u8; 26]> = Vec::new();
+    for (_, decryption_key) in keys {
+        let key = invert(&decryption_key);
+        if !distinct.contains(&key) {
+            distinct.push(key);
+        }
+    }
+    distinct
+}
+
+/// Climbs from a random decryption key, then `KICKS` times shakes up the best key found with
+/// a few random swaps and climbs again, which gets out of most local optima. Returns the
+/// score and the decryption key (cipher letter to plain letter).
+fn hill_climb(ciphertext: &[usize], rng: &mut impl Rng) -> (f32, [u8; 26]) {
+    let mut start: [u8; 26] = std::array::from_fn(|i| i as u8);
+    start.shuffle(rng);
+    let (mut best, mut best_key) = climb(ciphertext, start, rng);
+
+    for _ in 0..KICKS {
+        let mut key = best_key;
+        for _ in 0..KICK_SWAPS {
+            key.swap(rng.random_range(0..26), rng.random_range(0..26));
+        }
+        let (score, key) = climb(ciphertext, key, rng);
+        if score > best {
+            best = score;
+            best_key = key;
+        }
+    }
+    (best, best_key)
+}
+
+/// Swaps random pairs of letters in `key`, keeping swaps that improve the quadgram score,
+/// until `MAX_STALE_SWAPS` in a row have not.
+fn climb(ciphertext: &[usize], mut key: [u8; 26], rng: &mut impl Rng) -> (f32, [u8; 26]) {
+    let mut plaintext = vec![0; ciphertext.len()];
+    let mut fitness = |key: &[u8; 26]| {
+        for (p, &c) in plaintext.iter_mut().zip(ciphertext) {
+            *p = key[c] as usize;
+        }
+        quadgram_score_indices(&plaintext)
+    };
+
+    let mut best = fitness(&key);
+    let mut stale = 0;
+    while stale < MAX_STALE_SWAPS {
+        let i = rng.random_range(0..26);
+        let j = rng.random_range(0..26);
+        if i == j {
+            continue;
+        }
+
+        key.swap(i, j);
+        let score = fitness(&key);
+        if score > best {
+            best = score;
+            stale = 0;
+        } else {
+            key.swap(i, j);
+            stale += 1;
+        }
+    }
+    (best, key)
+}
+
+/// Replaces every letter `i` with `key[i]`, keeping case and anything that is not a letter.
+pub(crate) fn substitute(text: &str, key: &[u8; 26]) -> String {
+    text.chars().map(|c| {
+        if !c.is_ascii_alphabetic() {
+            return c;
+        }
+        let base = if c.is_uppercase() { b'A' } else { b'a' };
+        (key[(c as u8 - base) as usize] + base) as char
+    }).collect()
+}
+
+/// The inverse permutation of a key alphabet.
+pub(crate) fn invert(key: &[u8; 26]) -> [u8; 26] {
+    let mut inverse = [0; 26];
+    for (i, &k) in key.iter().enumerate() {
+        inverse[k as usize] = i as u8;
+    }
+    inverse
+}
+
+pub(crate) fn alphabet_to_string(key: &[u8; 26]) -> String {
+    key.iter().map(|&k| (k + b'a') as char).collect()
+}
+
+/// Parses a 26-letter cipher alphabet that uses every letter exactly once.
+pub(crate) fn parse_alphabet(key: &str) -> Result<[u8; 26], String> {
+    let letters: Vec<u8> = key.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            if c.is_ascii_alphabetic() {
+                Ok(c.to_ascii_lowercase() as u8 - b'a')
+            } else {
+                Err(format!("`{}` is not a letter", c))
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    let key: [u8; 26] = letters.try_into()
+        .map_err(|letters: Vec<u8>| format!("expected 26 letters, got {}", letters.len()))?;
+    let mut seen = [false; 26];
+    for &k in &key {
+        if std::mem::replace(&mut seen[k as usize], true) {
+            return Err(format!("`{}` appears more than once", (k + b'a') as char));
+        }
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::keeps_layout;
+
+    // About 260 letters of ordinary English, enough for quadgram scoring to pin down the key
+    const SAMPLE: &str = "My grandmother kept bees behind her cottage for nearly forty years. Every \
+        summer she would put on an old veil and gloves, light a small smoker, and open the hives \
+        while we watched from the kitchen window. She never seemed afraid, and she always said the \
+        bees knew exactly who she was.";
+
+    #[test]
+    fn round_trip() {
+        let key = parse_alphabet("qwertyuiopasdfghjklzxcvbnm").unwrap();
+        assert_eq!(Substitution.encrypt(&key, "Hello, World!"), "Itssg, Vgksr!");
+
+        let ciphertext = Substitution.encrypt(&key, SAMPLE);
+        assert_ne!(ciphertext, SAMPLE);
+        assert!(keeps_layout(SAMPLE, &ciphertext));
+        assert_eq!(Substitution.decrypt(&key, &ciphertext), SAMPLE);
+    }
+
+    #[test]
+    fn solves_sample_with_default_restarts() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let key = parse_alphabet("qwertyuiopasdfghjklzxcvbnm").unwrap();
+        let ciphertext = Substitution.encrypt(&key, SAMPLE);
+
+        let restarts = AttackParams::default().restarts;
+        let best = solve_substitution(&ciphertext, restarts, &mut StdRng::seed_from_u64(3260))[0];
+        assert_eq!(Substitution.decrypt(&best, &ciphertext), SAMPLE);
+    }
+}
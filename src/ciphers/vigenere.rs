@@ -0,0 +1,406 @@
+use crate::cipher::{parse_shift_key, AttackParams, CandidateHeap, Cipher, Setting};
+use crate::analysis::rank_periods;
+use crate::scoring::{chi_squared, english_words, letter_counts, Scorer};
+use crate::{Candidate, CipherType};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+
+// Number of most likely periods to attack when the period is detected automatically
+pub const AUTO_PERIOD_CANDIDATES: usize = 3;
+
+// Number of runner-up shifts per key position explored by the Vigenère beam search
+pub const BEAM_SHIFTS: usize = 3;
+
+// Most pairs of words the dictionary attack tries, so a long wordlist stays tractable
+pub const MAX_DICTIONARY_PAIRS: usize = 2_000_000;
+
+pub struct Vigenere;
+
+impl Cipher for Vigenere {
+    /// One shift per key letter, `a` = 0.
+    type Key = Vec<usize>;
+
+    fn cipher_type(&self) -> CipherType {
+        CipherType::Vigenere
+    }
+
+    fn id(&self) -> &'static str {
+        "vigenere"
+    }
+
+    fn name(&self) -> &'static str {
+        "Vigenère Cipher"
+    }
+
+    fn parse_key(&self, key: &str, _params: &AttackParams) -> Result<Vec<usize>, String> {
+        parse_shift_key(key)
+    }
+
+    fn encrypt(&self, key: &Vec<usize>, plaintext: &str) -> String {
+        shift_text(plaintext, key, shift_letter)
+    }
+
+    fn decrypt(&self, key: &Vec<usize>, ciphertext: &str) -> String {
+        shift_text(ciphertext, key, unshift_letter)
+    }
+
+    fn settings(&self, params: &AttackParams) -> Vec<Setting> {
+        let mut settings = if params.auto_period || params.dictionary_attack {
+            vec![Setting::MaxKeyLength("Max Period")]
+        } else {
+            vec![Setting::Period]
+        };
+        // The dictionary attack replaces the frequency analysis these two steer
+        if !params.dictionary_attack {
+            settings.extend([Setting::AutoPeriod, Setting::BeamWidth]);
+        }
+        settings.push(Setting::Dictionary);
+        settings
+    }
+
+    fn attack(&self, text: &str, params: &AttackParams) -> Vec<Candidate> {
+        if params.dictionary_attack {
+            return dictionary_attack(text, params, |key, text| self.decrypt(key, text));
+        }
+
+        let key_candidates = column_keys(text, params, params.beam_width, unshift_letter,
+            |key, text| self.decrypt(key, text));
+        rank_keys(text, key_candidates, params, |key, text| self.decrypt(key, text))
+    }
+}
+
+/// Vigenère encryption of one letter, both 0-25.
+pub(crate) fn shift_letter(shift: u8, letter: u8) -> u8 {
+    (letter + shift) % 26
+}
+
+/// Vigenère decryption of one letter, both 0-25.
+pub(crate) fn unshift_letter(shift: u8, letter: u8) -> u8 {
+    (letter + 26 - shift) % 26
+}
+
+/// Applies `f(shift, letter)` (both 0-25, result taken mod 26) to every letter, keeping case.
+/// The key advances on letters only, passing everything else through, so that the columns
+/// line up with `split_columns`.
+pub(crate) fn shift_text(text: &str, key: &[usize], f: impl Fn(u8, u8) -> u8) -> String {
+    let mut position = 0;
+    text.chars().map(|c| {
+        if !c.is_ascii_alphabetic() {
+            return c;
+        }
+        let shift = key[position % key.len()] as u8;
+        position += 1;
+        let base = if c.is_uppercase() { 'A' } else { 'a' } as u8;
+        (f(shift, c as u8 - base) % 26 + base) as char
+    }).collect()
+}
+
+/// Splits the letters of the text into `period` columns, letter `i` going to column
+/// `i % period`. Anything that is not a letter is left out.
+pub(crate) fn split_columns(text: &str, period: usize) -> Vec<Vec<char>> {
+    let mut char_groups: Vec<Vec<char>> = vec![Vec::new(); period];
+    for (i, c) in text.chars().filter(|c| c.is_ascii_alphabetic()).enumerate() {
+        char_groups[i % period].push(c);
+    }
+    char_groups
+}
+
+/// Every shift for one column, best first, with the chi-squared statistic of the column's
+/// letters decrypted by `decrypt(shift, letter)` against English letter frequencies.
+pub fn rank_column_shifts(group: &[char], decrypt: impl Fn(u8, u8) -> u8) -> Vec<(usize, f32)> {
+    let counts = letter_counts(&group.iter().collect::<String>());
+
+    let mut ranking: Vec<(usize, f32)> = (0..26).map(|shift| {
+        let mut shifted = [0usize; 26];
+        for (letter, &count) in counts.iter().enumerate() {
+            shifted[(decrypt(shift as u8, letter as u8) % 26) as usize] += count;
+        }
+        (shift, chi_squared(&shifted))
+    }).collect();
+
+    ranking.sort_by(|a, b| a.1.total_cmp(&b.1));
+    ranking
+}
+
+/// Starting from `best_key`, revisits each position in turn and tries its runner-up shifts,
+/// keeping the `beam_width` best keys by `scorer`. A width of 0 or 1 returns `best_key` alone.
+pub(crate) fn beam_search(
+    text: &str,
+    rankings: &[Vec<(usize, f32)>],
+    best_key: Vec<usize>,
+    beam_width: usize,
+    scorer: &dyn Scorer,
+    decrypt: impl Fn(&Vec<usize>, &str) -> String,
+) -> Vec<Vec<usize>> {
+    let mut beam = vec![best_key];
+    if beam_width <= 1 {
+        return beam;
+    }
+
+    for (position, ranking) in rankings.iter().enumerate() {
+        let mut next: Vec<(f32, Vec<usize>)> = beam.iter()
+            .flat_map(|key| ranking.iter().take(BEAM_SHIFTS).map(move |&(shift, _)| {
+                let mut key = key.clone();
+                key[position] = shift;
+                key
+            }))
+            .unique()
+            .map(|key| (scorer.score(&decrypt(&key, text)), key))
+            .collect();
+
+        next.sort_by(|a, b| b.0.total_cmp(&a.0));
+        beam = next.into_iter().take(beam_width).map(|(_, key)| key).collect();
+    }
+    beam
+}
+
+/// Solves every key position independently for each period to try, taking the shift whose
+/// column decrypted by `decrypt_letter(shift, letter)` best fits English, then widens each
+/// key with a beam search of `beam_width` keys.
+pub(crate) fn column_keys(
+    text: &str,
+    params: &AttackParams,
+    beam_width: usize,
+    decrypt_letter: impl Fn(u8, u8) -> u8 + Copy,
+    decrypt: impl Fn(&Vec<usize>, &str) -> String + Copy,
+) -> Vec<Vec<usize>> {
+    let mut key_candidates: Vec<Vec<usize>> = Vec::new();
+    for period in periods_to_try(text, params) {
+        params.log(format_args!("Starting decryption with period {}", period));
+
+        let rankings: Vec<Vec<(usize, f32)>> = split_columns(text, period).iter().enumerate()
+            .map(|(i, group)| {
+                let ranking = rank_column_shifts(group, decrypt_letter);
+                params.log(format_args!("Best shifts at position {}: {}", i,
+                    ranking.iter().take(BEAM_SHIFTS)
+                        .map(|&(shift, chi)| format!("{} ({:.1})", (shift as u8 + b'a') as char, chi))
+                        .collect::<Vec<String>>().join(", ")));
+                ranking
+            })
+            .collect();
+
+        let best_key: Vec<usize> = rankings.iter().map(|ranking| ranking[0].0).collect();
+        params.log(format_args!("Best key for period {}: {}", period, key_to_string(&best_key)));
+
+        key_candidates.extend(beam_search(text, &rankings, best_key, beam_width, &*params.scorer, decrypt));
+    }
+    key_candidates
+}
+
+/// Periods to attack: the user's period, or the likeliest few when auto-detecting.
+pub(crate) fn periods_to_try(text: &str, params: &AttackParams) -> Vec<usize> {
+    if !params.auto_period {
+        return vec![params.period];
+    }
+
+    let ranked = rank_periods(text, params.max_key_length);
+    for estimate in ranked.iter().take(AUTO_PERIOD_CANDIDATES) {
+        params.log(format_args!("Likely period {} (score {:.3}, IC {:.3}, Kasiski {:.2})",
+            estimate.period, estimate.score, estimate.average_ic, estimate.kasiski));
+    }
+    ranked.iter().take(AUTO_PERIOD_CANDIDATES).map(|estimate| estimate.period).collect()
+}
+
+/// Scores every candidate key and keeps the 5 best decryptions.
+pub(crate) fn rank_keys(
+    text: &str,
+    key_candidates: Vec<Vec<usize>>,
+    params: &AttackParams,
+    decrypt: impl Fn(&Vec<usize>, &str) -> String,
+) -> Vec<Candidate> {
+    // A key repeated end to end decrypts exactly like the key itself, so `lemonlemon` from
+    // period 10 is the same candidate as `lemon`
+    let key_candidates: Vec<Vec<usize>> = key_candidates.iter()
+        .map(|key| shortest_repeat(key).to_vec())
+        .unique()
+        .collect();
+    params.log(format_args!("Generated {} key candidates", key_candidates.len()));
+
+    let heap = CandidateHeap::new(5);
+    for (i, key) in key_candidates.into_iter().enumerate() {
+        if i % 100 == 0 {
+            params.log(format_args!("Testing key candidate {}", i));
+        }
+
+        let result = decrypt(&key, text);
+        let score = params.scorer.score(&result);
+        heap.push(score, result, key);
+    }
+
+    heap.into_candidates(|key, text| format!("Key: {}\nDecryption:\n{}", key_to_string(key), text))
+}
+
+/// Tries every word of the bundled dictionary and `params.wordlist` as the key, whatever its
+/// length, then pairs of those words together at most `params.max_key_length` letters long. Pairs grow
+/// quadratically with a long wordlist, so they begin with the wordlist's words, stop after
+/// `MAX_DICTIONARY_PAIRS` and are scored as they are made rather than collected first.
+pub(crate) fn dictionary_attack(
+    text: &str,
+    params: &AttackParams,
+    decrypt: impl Fn(&Vec<usize>, &str) -> String + Sync,
+) -> Vec<Candidate> {
+    // Repeated words such as `abab` decrypt exactly like their shortest unit
+    let to_shifts = |word: &str| -> Vec<usize> {
+        let shifts: Vec<usize> = word.bytes()
+            .filter(|b| b.is_ascii_alphabetic())
+            .map(|b| (b.to_ascii_lowercase() - b'a') as usize)
+            .collect();
+        shortest_repeat(&shifts).to_vec()
+    };
+    // The wordlist's own words come first, in the order given, then the bundled words in
+    // file order, so the cap always cuts off the same pairs
+    let words: Vec<Vec<usize>> = params.wordlist.iter()
+        .map(|word| to_shifts(word))
+        .chain(english_words().map(to_shifts))
+        .filter(|word| !word.is_empty())
+        .unique()
+        .collect();
+
+    let short_words: Vec<&Vec<usize>> = words.iter()
+        .filter(|word| word.len() < params.max_key_length)
+        .collect();
+    let pairs = short_words.iter()
+        .flat_map(|first| short_words.iter()
+            .filter(|second| first.len() + second.len() <= params.max_key_length)
+            .map(|second| [first.as_slice(), second.as_slice()].concat()))
+        .filter(|key| shortest_repeat(key).len() == key.len())
+        .take(MAX_DICTIONARY_PAIRS);
+    params.log(format_args!("Trying {} dictionary words and pairs of {} words, at most {}",
+        words.len(), short_words.len(), MAX_DICTIONARY_PAIRS));
+
+    // Different pairs can spell the same key, so the heap skips keys it already holds
+    let heap = CandidateHeap::new(5);
+    words.iter().cloned().chain(pairs).par_bridge().for_each(|key| {
+        let result = decrypt(&key, text);
+        let score = params.scorer.score(&result);
+        heap.push_distinct(score, result, key);
+    });
+
+    heap.into_candidates(|key, text| format!("Key: {}\nDecryption:\n{}", key_to_string(key), text))
+}
+
+/// The shortest prefix that gives `key` when repeated, such as `lemon` for `lemonlemon`.
+pub(crate) fn shortest_repeat(key: &[usize]) -> &[usize] {
+    let n = key.len();
+    let period = (1..n)
+        .find(|&p| n.is_multiple_of(p) && (p..n).all(|i| key[i] == key[i - p]))
+        .unwrap_or(n);
+    &key[..period]
+}
+
+pub(crate) fn key_to_string(key: &[usize]) -> String {
+    key.iter()
+        .map(|&shift| (shift as u8 + b'a') as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::{keeps_layout, SAMPLE_TEXT};
+    use crate::scoring::QuadgramScorer;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn repeated_keys_are_reduced_to_their_shortest_unit() {
+        assert_eq!(shortest_repeat(&[1, 2, 1, 2, 1, 2]), [1, 2]);
+        assert_eq!(shortest_repeat(&[1, 2, 1]), [1, 2, 1]);
+        assert_eq!(shortest_repeat(&[3]), [3]);
+    }
+
+    #[test]
+    fn auto_period_does_not_return_multiples_of_the_key() {
+        let key = parse_shift_key("lemon").unwrap();
+        let ciphertext = Vigenere.encrypt(&key, SAMPLE_TEXT);
+        let params = AttackParams { auto_period: true, max_key_length: 10, ..AttackParams::default() };
+
+        let candidates = Vigenere.attack(&ciphertext, &params);
+        assert!(candidates[0].text.starts_with("Key: lemon\n"));
+        assert!(candidates.iter().all(|candidate| !candidate.text.starts_with("Key: lemonlemon")));
+    }
+
+    #[test]
+    fn dictionary_attack_finds_pairs_of_words() {
+        let key = parse_shift_key("darksea").unwrap();
+        let ciphertext = Vigenere.encrypt(&key, SAMPLE_TEXT);
+        let params = AttackParams {
+            dictionary_attack: true,
+            max_key_length: 7,
+            scorer: Arc::new(QuadgramScorer),
+            ..AttackParams::default()
+        };
+
+        let candidates = Vigenere.attack(&ciphertext, &params);
+        assert!(candidates[0].text.starts_with("Key: darksea\n"));
+    }
+
+    #[test]
+    fn dictionary_attack_pairs_wordlist_words() {
+        let key = parse_shift_key("lynxsea").unwrap();
+        let ciphertext = Vigenere.encrypt(&key, SAMPLE_TEXT);
+        let params = AttackParams {
+            dictionary_attack: true,
+            max_key_length: 7,
+            wordlist: Arc::new(vec!["Lynx".to_owned()]),
+            scorer: Arc::new(QuadgramScorer),
+            ..AttackParams::default()
+        };
+
+        let candidates = Vigenere.attack(&ciphertext, &params);
+        assert!(candidates[0].text.starts_with("Key: lynxsea\n"));
+    }
+
+    #[test]
+    fn wordlist_words_are_tried_whatever_their_length() {
+        let key = parse_shift_key("quartermaster").unwrap();
+        let ciphertext = Vigenere.encrypt(&key, SAMPLE_TEXT);
+        let params = AttackParams {
+            dictionary_attack: true,
+            max_key_length: 4,
+            wordlist: Arc::new(vec!["Quartermaster".to_owned()]),
+            scorer: Arc::new(QuadgramScorer),
+            ..AttackParams::default()
+        };
+
+        let candidates = Vigenere.attack(&ciphertext, &params);
+        assert!(candidates[0].text.starts_with("Key: quartermaster\n"));
+    }
+
+    #[test]
+    fn beam_search_recovers_shifts_ranked_second() {
+        let key = parse_shift_key("lemon").unwrap();
+        let ciphertext = Vigenere.encrypt(&key, SAMPLE_TEXT);
+        // Demote the true shift of every column to runner-up, as short columns often do
+        let rankings: Vec<Vec<(usize, f32)>> = split_columns(&ciphertext, key.len()).iter()
+            .map(|group| {
+                let mut ranking = rank_column_shifts(group, unshift_letter);
+                ranking.swap(0, 1);
+                ranking
+            })
+            .collect();
+        let best_key: Vec<usize> = rankings.iter().map(|ranking| ranking[0].0).collect();
+        let decrypt = |key: &Vec<usize>, text: &str| Vigenere.decrypt(key, text);
+
+        let narrow = beam_search(&ciphertext, &rankings, best_key.clone(), 1, &QuadgramScorer, decrypt);
+        assert_eq!(narrow.len(), 1);
+        assert_eq!(narrow[0], best_key);
+
+        let wide = beam_search(&ciphertext, &rankings, best_key, 4, &QuadgramScorer, decrypt);
+        assert_eq!(wide.len(), 4);
+        assert_eq!(wide[0], key);
+    }
+
+    #[test]
+    fn round_trip_keeps_case_and_punctuation() {
+        let key = parse_shift_key("lemon").unwrap();
+        assert_eq!(Vigenere.encrypt(&key, "ATTACKATDAWN"), "LXFOPVEFRNHR");
+        assert_eq!(Vigenere.encrypt(&key, "Attack at dawn!"), "Lxfopv ef rnhr!");
+
+        let ciphertext = Vigenere.encrypt(&key, SAMPLE_TEXT);
+        assert_ne!(ciphertext, SAMPLE_TEXT);
+        assert!(keeps_layout(SAMPLE_TEXT, &ciphertext));
+        assert_eq!(Vigenere.decrypt(&key, &ciphertext), SAMPLE_TEXT);
+    }
+}
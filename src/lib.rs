@@ -0,0 +1,319 @@
+//! Core solver for the classical ciphers covered in COMP3260.
+//!
+//! The GUI in `main.rs` is a thin front end over this crate: everything needed to
+//! decrypt, score or analyse a ciphertext lives here so it can be driven from scripts.
+
+pub mod analysis;
+mod cipher;
+pub mod ciphers;
+pub mod scoring;
+
+pub use cipher::{
+    keyword_order, keywords_for_order, keywords_for_ranks, parse_permutation, parse_shift_key, AnyCipher,
+    AttackParams, Cipher, Setting, CIPHERS,
+};
+pub use scoring::{quadgram_score, scorer_from_id, scorers, Scorer};
+use ciphers::{Beaufort, Columnar, Periodic, Vigenere};
+
+// Common trigrams in English with their frequencies
+pub const COMMON_TRIGRAMS: [(&str, usize); 20] = [
+    ("the", 100),  // Most common trigram
+    ("and", 80),
+    ("ing", 70),
+    ("ent", 60),
+    ("ion", 55),
+    ("her", 50),
+    ("for", 45),
+    ("tha", 40),
+    ("nth", 35),
+    ("int", 30),
+    ("ere", 25),
+    ("tio", 25),
+    ("ter", 25),
+    ("est", 25),
+    ("ers", 25),
+    ("ati", 25),
+    ("hat", 25),
+    ("ate", 25),
+    ("all", 25),
+    ("eth", 25),
+];
+
+// Common bigrams in English with their frequencies
+pub const COMMON_BIGRAMS: [(&str, usize); 15] = [
+    ("th", 100),  // Most common bigram
+    ("he", 90),
+    ("in", 80),
+    ("er", 70),
+    ("an", 60),
+    ("re", 50),
+    ("on", 45),
+    ("at", 40),
+    ("en", 35),
+    ("nd", 30),
+    ("ti", 30),
+    ("es", 30),
+    ("or", 30),
+    ("te", 30),
+    ("of", 30),
+];
+
+// Common English words with their frequencies
+pub const COMMON_WORDS: [(&str, usize); 30] = [
+    ("the", 300),  // Most common word
+    ("be", 270),
+    ("to", 240),
+    ("of", 210),
+    ("and", 180),
+    ("a", 165),
+    ("in", 150),
+    ("that", 135),
+    ("have", 120),
+    ("i", 105),
+    ("it", 90),
+    ("for", 90),
+    ("not", 90),
+    ("on", 90),
+    ("with", 90),
+    ("he", 90),
+    ("as", 90),
+    ("you", 90),
+    ("do", 90),
+    ("at", 90),
+    ("this", 90),
+    ("but", 90),
+    ("his", 90),
+    ("by", 90),
+    ("from", 90),
+    ("they", 90),
+    ("we", 90),
+    ("say", 90),
+    ("her", 90),
+    ("she", 90),
+];
+
+// Character frequencies in English (in order of frequency)
+pub const CHAR_FREQUENCIES: [(char, usize); 12] = [
+    ('e', 100),  // Most common letter
+    ('t', 90),
+    ('a', 80),
+    ('o', 75),
+    ('i', 70),
+    ('n', 65),
+    ('s', 60),
+    ('h', 55),
+    ('r', 50),
+    ('d', 45),
+    ('l', 40),
+    ('c', 35),
+];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CipherType {
+    Columnar,
+    DoubleColumnar,
+    Myszkowski,
+    Amsco,
+    Periodic,
+    RailFence,
+    Redefence,
+    Route,
+    Vigenere,
+    Beaufort,
+    Autokey,
+    Caesar,
+    Atbash,
+    Affine,
+    Substitution,
+    Playfair,
+    Hill,
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub name: String,
+    pub score: f32,
+    pub text: String,
+}
+
+/// A cipher with the settings to attack it, and the key to use when one is known.
+#[derive(Clone)]
+pub struct Decrypter {
+    pub cipher_type: CipherType,
+    pub key: Option<String>,
+    pub params: AttackParams,
+}
+
+impl Default for Decrypter {
+    fn default() -> Self {
+        Self {
+            cipher_type: CipherType::Columnar,
+            key: None,
+            params: AttackParams::default(),
+        }
+    }
+}
+
+pub fn compute_factors(n: usize) -> Vec<usize> {
+    let mut result: Vec<usize> = Vec::new();
+    result.push(1);
+    if n > 1 {
+        result.push(n);
+    }
+    let int_sqrt = (n as f32).sqrt() as usize;
+    for i in 2..=int_sqrt {
+        if n.is_multiple_of(i) {
+            result.push(i);
+            if i != n / i {
+                result.push(n / i);
+            }
+        }
+    }
+
+    // Heuristic: sort the factors from smallest to biggest
+    result.sort();
+    result
+}
+
+impl Decrypter {
+    fn params_with_transpose(&self, transpose: bool) -> AttackParams {
+        AttackParams { transpose, ..self.params.clone() }
+    }
+
+    pub fn decrypt(&self, text: &str) -> Vec<Candidate> {
+        self.decrypt_with_transpose(text, self.params.transpose)
+    }
+
+    /// Attacks a columnar transposition read row by row, whatever `params.transpose` says;
+    /// `decrypt_columnar_with_transpose` reads the grid transposed.
+    pub fn decrypt_columnar(&self, text: &str) -> Vec<Candidate> {
+        self.decrypt_columnar_with_transpose(text, false)
+    }
+
+    pub fn decrypt_periodic(&self, text: &str) -> Vec<Candidate> {
+        Cipher::attack(&Periodic, text, &self.params_with_transpose(false))
+    }
+
+    pub fn decrypt_vigenere(&self, text: &str) -> Vec<Candidate> {
+        Cipher::attack(&Vigenere, text, &self.params_with_transpose(false))
+    }
+
+    pub fn decrypt_beaufort(&self, text: &str) -> Vec<Candidate> {
+        Cipher::attack(&Beaufort, text, &self.params_with_transpose(false))
+    }
+
+    pub fn decrypt_with_transpose(&self, text: &str, transpose: bool) -> Vec<Candidate> {
+        self.cipher_type.cipher().attack(text, &self.params_with_transpose(transpose))
+    }
+
+    /// Encrypts `text` with `self.key` using the selected cipher and `self.params`.
+    pub fn encrypt(&self, text: &str) -> Result<String, String> {
+        let key = self.key.as_deref().ok_or("no key given")?;
+        self.cipher_type.cipher().encrypt_with(key, text, &self.params)
+    }
+
+    /// Decrypts `text` with the known `self.key` and `self.params` instead of searching for one.
+    pub fn decrypt_with_key(&self, text: &str) -> Result<String, String> {
+        let key = self.key.as_deref().ok_or("no key given")?;
+        self.cipher_type.cipher().decrypt_with(key, text, &self.params)
+    }
+
+    pub fn decrypt_columnar_with_transpose(&self, text: &str, transpose: bool) -> Vec<Candidate> {
+        Cipher::attack(&Columnar, text, &self.params_with_transpose(transpose))
+    }
+
+    pub fn periodic_inv(&self, text: &str, key: &[usize]) -> String {
+        ciphers::periodic_inv(text, key)
+    }
+
+    pub fn columnar_inv(&self, text: &str, key: &[usize], transpose: bool) -> String {
+        ciphers::columnar_inv(text, key, transpose)
+    }
+
+    pub fn railfence_inv(&self, text: &str, order: &[usize], offset: usize) -> String {
+        ciphers::railfence_inv(text, order, offset)
+    }
+
+    pub fn english_score(text: &str) -> usize {
+        let text = text.to_lowercase();
+        let mut score = 0;
+
+        // Score based on trigrams
+        for (trigram, weight) in COMMON_TRIGRAMS {
+            let count = text.matches(trigram).count();
+            score += count * weight;
+        }
+
+        // Score based on bigrams
+        for (bigram, weight) in COMMON_BIGRAMS {
+            let count = text.matches(bigram).count();
+            score += count * weight;
+        }
+
+        // Score based on common words
+        for (word, weight) in COMMON_WORDS {
+            let count = text.matches(word).count();
+            score += count * weight;
+        }
+
+        // Score based on character frequencies
+        let text_chars: Vec<char> = text.chars().collect();
+        for (c, weight) in CHAR_FREQUENCIES {
+            let count = text_chars.iter().filter(|&&x| x == c).count();
+            score += count * weight;
+        }
+
+        score
+    }
+
+    pub fn print_index_of_coincidence_table(text: &str) {
+        for period in 1..=10 {
+            let output = Self::index_of_coincidence(text, period);
+            let avg = output.iter().sum::<f32>() / output.len() as f32;
+            println!("p={}: {:?} | {:?}", period, (avg - 0.066).abs(), output);
+        }
+    }
+
+    pub fn index_of_coincidence(text: &str, period: usize) -> Vec<f32> {
+        // 1. split text into d period components
+        // 0 1 2 3 4 5 6 7 8 9 10
+        // 0 1 2 0 1 2 0 1 2 0 1
+
+        let mut char_groups: Vec<Vec<char>> = vec![Vec::new(); period];
+        let mut output: Vec<f32> = vec![0.0; period];
+
+        // Only letters are counted, as the polyalphabetic ciphers advance their key
+        for (i, char) in text.chars().filter(|c| c.is_ascii_alphabetic()).enumerate() {
+            char_groups[i % period].push(char);
+        }
+
+        for (i, char_group) in char_groups.into_iter().enumerate() {
+            // 2. get frequency table for the char_group
+            let mut freq_table: Vec<f32> = vec![0.0; 26];
+            let mut n = 0;
+            for char in char_group {
+                freq_table[char.to_ascii_lowercase() as usize - 'a' as usize] += 1.0;
+                n += 1;
+            }
+            if n < 2 {
+                continue;
+            }
+
+            //3.
+            // output[i % period] +=
+            //     Decrypter::get_frequency(char) * (Decrypter::get_frequency(char) - 1.0) /
+            //     (period as f32 * (period as f32 - 1.));
+            for count in freq_table {
+                output[i % period] += count * (count - 1.0) / (n as f32 * (n as f32 - 1.));
+            }
+        }
+
+        //println!("Char groups: {:?}", output);
+
+        output
+    }
+
+    pub fn beaufort_key_letter(ciphertext_letter: char) -> char {
+        ciphers::beaufort_key_letter(ciphertext_letter)
+    }
+}
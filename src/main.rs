@@ -1,109 +1,21 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
-use eframe::egui;
-use itertools::{structs, Itertools, Permutations};
-
-use std::collections::BinaryHeap;
-use std::cmp::Reverse;
-use std::sync::Mutex;
-use std::sync::mpsc;
-use rayon::prelude::*;
-
-// Common trigrams in English with their frequencies
-const COMMON_TRIGRAMS: [(&str, usize); 20] = [
-    ("the", 100),  // Most common trigram
-    ("and", 80),
-    ("ing", 70),
-    ("ent", 60),
-    ("ion", 55),
-    ("her", 50),
-    ("for", 45),
-    ("tha", 40),
-    ("nth", 35),
-    ("int", 30),
-    ("ere", 25),
-    ("tio", 25),
-    ("ter", 25),
-    ("est", 25),
-    ("ers", 25),
-    ("ati", 25),
-    ("hat", 25),
-    ("ate", 25),
-    ("all", 25),
-    ("eth", 25),
-];
+mod workbench;
 
-// Number of top letters to check for each position in Beaufort cipher
-const BEAUFORT_TOP_LETTERS: usize = 2;
-
-// Common bigrams in English with their frequencies
-const COMMON_BIGRAMS: [(&str, usize); 15] = [
-    ("th", 100),  // Most common bigram
-    ("he", 90),
-    ("in", 80),
-    ("er", 70),
-    ("an", 60),
-    ("re", 50),
-    ("on", 45),
-    ("at", 40),
-    ("en", 35),
-    ("nd", 30),
-    ("ti", 30),
-    ("es", 30),
-    ("or", 30),
-    ("te", 30),
-    ("of", 30),
-];
+use eframe::egui;
+use cipher_solver_comp3260::{
+    compute_factors, scorers, AttackParams, Candidate, CipherType, Decrypter, Scorer, Setting, CIPHERS,
+};
+use cipher_solver_comp3260::analysis::{friedman_estimate, identify_cipher, kasiski_examination, rank_periods};
+use cipher_solver_comp3260::ciphers::{ColumnarSearch, MAX_DICTIONARY_PAIRS};
+use cipher_solver_comp3260::scoring::HeuristicScorer;
+use workbench::Workbench;
 
-// Common English words with their frequencies
-const COMMON_WORDS: [(&str, usize); 30] = [
-    ("the", 300),  // Most common word
-    ("be", 270),
-    ("to", 240),
-    ("of", 210),
-    ("and", 180),
-    ("a", 165),
-    ("in", 150),
-    ("that", 135),
-    ("have", 120),
-    ("i", 105),
-    ("it", 90),
-    ("for", 90),
-    ("not", 90),
-    ("on", 90),
-    ("with", 90),
-    ("he", 90),
-    ("as", 90),
-    ("you", 90),
-    ("do", 90),
-    ("at", 90),
-    ("this", 90),
-    ("but", 90),
-    ("his", 90),
-    ("by", 90),
-    ("from", 90),
-    ("they", 90),
-    ("we", 90),
-    ("say", 90),
-    ("her", 90),
-    ("she", 90),
-];
+use std::sync::{mpsc, Arc};
 
-// Character frequencies in English (in order of frequency)
-const CHAR_FREQUENCIES: [(char, usize); 12] = [
-    ('e', 100),  // Most common letter
-    ('t', 90),
-    ('a', 80),
-    ('o', 75),
-    ('i', 70),
-    ('n', 65),
-    ('s', 60),
-    ('h', 55),
-    ('r', 50),
-    ('d', 45),
-    ('l', 40),
-    ('c', 35),
-];
+/// Title and candidates of a finished background job, with the ciphertext and key of the
+/// best candidate when the attack reports cipher alphabets
+type Results = (&'static str, Vec<Candidate>, Option<(String, [u8; 26])>);
 
 fn main() -> eframe::Result {
     let options = eframe::NativeOptions {
@@ -113,50 +25,55 @@ fn main() -> eframe::Result {
     eframe::run_native(
         "My egui App",
         options,
-        Box::new(|cc| {
+        Box::new(|_cc| {
             Ok(Box::<MyApp>::default())
         }),
     )
 }
 
 #[derive(PartialEq, Clone, Copy)]
-enum CipherType {
-    Columnar,
-    Periodic,
-    Vigenere,
-    Beaufort,
-}
-
-#[derive(Clone)]
-struct Candidate {
-    name: String,
-    score: f32,
-    text: String,
+enum Mode {
+    Solve,
+    Encrypt,
+    Decrypt,
 }
 
 struct MyApp {
     my_string: String,
+    mode: Mode,
+    key: String,
+    result_title: &'static str,
     max_key_length: String,
-    result_text: String,
     show_result: bool,
     factors: Option<Vec<usize>>,
     decryption_in_progress: bool,
-    result_receiver: Option<mpsc::Receiver<Vec<Candidate>>>,
+    result_receiver: Option<mpsc::Receiver<Results>>,
     transpose: bool,
     cipher_type: CipherType,
     period: String,
     check_all_periods: bool,
+    auto_period: bool,
+    beam_width: String,
+    restarts: String,
+    crib: String,
+    columnar_search: ColumnarSearch,
+    dictionary_attack: bool,
+    wordlist: Arc<Vec<String>>,
+    scorer: Arc<dyn Scorer>,
     selected_tab: usize,
     max_ic_period: f32,
     candidates: Vec<Candidate>,
+    workbench: Workbench,
 }
 
 impl Default for MyApp {
     fn default() -> Self {
         Self {
             my_string: "".to_owned(),
+            mode: Mode::Solve,
+            key: "".to_owned(),
+            result_title: "Decryption Results",
             max_key_length: "8".to_owned(),
-            result_text: "".to_owned(),
             show_result: false,
             factors: None,
             decryption_in_progress: false,
@@ -165,14 +82,158 @@ impl Default for MyApp {
             cipher_type: CipherType::Columnar,
             period: "3".to_owned(),
             check_all_periods: false,
+            auto_period: false,
+            beam_width: "0".to_owned(),
+            restarts: "20".to_owned(),
+            crib: String::new(),
+            columnar_search: ColumnarSearch::Exhaustive,
+            dictionary_attack: false,
+            wordlist: Arc::new(Vec::new()),
+            scorer: Arc::new(HeuristicScorer),
             selected_tab: 0,
             max_ic_period: 10.0,
             candidates: Vec::new(),
+            workbench: Workbench::default(),
         }
     }
 }
 
 impl MyApp {
+    /// The selected cipher with the settings typed into the form.
+    fn decrypter(&self, key: Option<String>) -> Decrypter {
+        let defaults = AttackParams::default();
+        Decrypter {
+            cipher_type: self.cipher_type,
+            key,
+            params: AttackParams {
+                max_key_length: self.max_key_length.parse::<usize>().unwrap_or(defaults.max_key_length),
+                period: self.period.parse::<usize>().unwrap_or(defaults.period),
+                check_all_periods: self.check_all_periods,
+                auto_period: self.auto_period,
+                beam_width: self.beam_width.parse::<usize>().unwrap_or(defaults.beam_width),
+                restarts: self.restarts.parse::<usize>().unwrap_or(defaults.restarts),
+                crib: self.crib.clone(),
+                columnar_search: self.columnar_search,
+                dictionary_attack: self.dictionary_attack,
+                wordlist: self.wordlist.clone(),
+                transpose: self.transpose,
+                verbose: cfg!(debug_assertions),
+                scorer: self.scorer.clone(),
+            },
+        }
+    }
+
+    /// One row of the form for a setting the selected cipher's attack reads.
+    fn setting_ui(&mut self, ui: &mut egui::Ui, setting: Setting) {
+        ui.horizontal(|ui| match setting {
+            Setting::Transpose => {
+                ui.checkbox(&mut self.transpose, "Transpose");
+            }
+            Setting::MaxKeyLength(label) => {
+                ui.label(format!("{}:", label));
+                ui.add_space(8.0);
+                ui.add(egui::TextEdit::singleline(&mut self.max_key_length)
+                    .desired_width(60.0));
+            }
+            Setting::Period => {
+                ui.label("Period:");
+                ui.add_space(8.0);
+                ui.add(egui::TextEdit::singleline(&mut self.period)
+                    .desired_width(60.0));
+            }
+            Setting::CheckAllPeriods => {
+                ui.checkbox(&mut self.check_all_periods, "Check all periods up to the max period");
+            }
+            Setting::AutoPeriod => {
+                ui.checkbox(&mut self.auto_period, "Auto-detect period");
+            }
+            Setting::BeamWidth => {
+                ui.label("Beam Width:");
+                ui.add_space(8.0);
+                ui.add(egui::TextEdit::singleline(&mut self.beam_width)
+                    .desired_width(60.0))
+                    .on_hover_text("Keys kept while trying runner-up shifts, 0 for the best key only");
+            }
+            Setting::Restarts => {
+                ui.label("Restarts:");
+                ui.add_space(8.0);
+                ui.add(egui::TextEdit::singleline(&mut self.restarts)
+                    .desired_width(60.0));
+            }
+            Setting::Crib => {
+                ui.label("Crib:");
+                ui.add_space(8.0);
+                ui.add(egui::TextEdit::singleline(&mut self.crib)
+                    .desired_width(200.0)
+                    .hint_text("known plaintext (optional)"));
+            }
+            Setting::ColumnarSearch => {
+                if self.transpose && !self.columnar_search.supports_transpose() {
+                    self.columnar_search = ColumnarSearch::HillClimb;
+                }
+                ui.label("Search:");
+                ui.add_space(8.0);
+                egui::ComboBox::from_id_salt("columnar_search")
+                    .selected_text(self.columnar_search.name())
+                    .show_ui(ui, |ui| {
+                        for search in ColumnarSearch::ALL {
+                            ui.add_enabled_ui(!self.transpose || search.supports_transpose(), |ui| {
+                                ui.selectable_value(&mut self.columnar_search, search, search.name());
+                            }).response.on_disabled_hover_text("Not available with Transpose");
+                        }
+                    });
+            }
+            Setting::Dictionary => {
+                ui.checkbox(&mut self.dictionary_attack, "Dictionary attack")
+                    .on_hover_text(format!("Try every dictionary and wordlist word of any length as the key, then pairs of them up to the max \
+                        period, wordlist words first and at most {} pairs", MAX_DICTIONARY_PAIRS));
+                ui.add_space(16.0);
+                if ui.button("📂 Wordlist").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .pick_file()
+                    {
+                        match std::fs::read_to_string(&path) {
+                            Ok(contents) => {
+                                self.wordlist = Arc::new(contents
+                                    .split_whitespace()
+                                    .map(str::to_owned)
+                                    .collect());
+                                self.dictionary_attack = true;
+                            }
+                            Err(e) => println!("Error reading file: {}", e),
+                        }
+                    }
+                }
+                if !self.wordlist.is_empty() {
+                    ui.label(format!("{} extra words", self.wordlist.len()));
+                }
+            }
+            Setting::Workbench => {
+                if ui.button("🛠 Workbench").clicked() {
+                    self.workbench.open_seeded(&self.my_string);
+                }
+            }
+        });
+    }
+
+    fn run_with_key(&mut self) {
+        let decrypter = self.decrypter(Some(self.key.clone()));
+
+        let (title, result) = if self.mode == Mode::Encrypt {
+            ("Ciphertext", decrypter.encrypt(&self.my_string))
+        } else {
+            ("Plaintext", decrypter.decrypt_with_key(&self.my_string))
+        };
+
+        self.candidates = vec![match result {
+            Ok(text) => Candidate { name: title.to_string(), score: 0.0, text },
+            Err(e) => Candidate { name: "Error".to_string(), score: 0.0, text: format!("Invalid key: {}", e) },
+        }];
+        self.result_title = if self.mode == Mode::Encrypt { "Encryption Result" } else { "Decryption Result" };
+        self.show_result = true;
+        self.selected_tab = 0;
+    }
+
     fn show_candidates_dialog(&mut self, ctx: &egui::Context, title: &str) {
         egui::Window::new(title)
             .collapsible(false)
@@ -203,7 +264,7 @@ impl MyApp {
                                     ui.label(format!("Score: {:.3}", candidate.score));
                                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                                         if ui.button("Copy Text").clicked() {
-                                            ui.output_mut(|o| o.copied_text = candidate.text.clone());
+                                            ui.ctx().copy_text(candidate.text.clone());
                                         }
                                         ui.label("📋");
                                     });
@@ -232,14 +293,88 @@ impl MyApp {
     }
 }
 
+/// Per-period IC values, the combined period ranking and a Kasiski examination of `text`,
+/// as tabs for the results window.
+fn ic_results(text: &str, max_period: usize) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+
+    // Create overview tab first
+    let mut overview_text = String::new();
+    for period in 1..=max_period {
+        let output = Decrypter::index_of_coincidence(text, period);
+        let avg = output.iter().sum::<f32>() / output.len() as f32;
+        let score = (avg - 0.066).abs(); // Closer to English IC is better
+
+        overview_text.push_str(&format!("p={}: {:.3} | [{}]\n", period, (avg - 0.066).abs(),
+            output.iter().map(|&x| format!("{:.3}", x)).collect::<Vec<String>>().join(", ")));
+
+        candidates.push(Candidate {
+            name: format!("p={}", period),
+            score,
+            text: format!("IC: {:.3}\nValues: [{}]", avg, 
+                output.iter().map(|&x| format!("{:.3}", x)).collect::<Vec<String>>().join(", ")),
+        });
+    }
+
+    // Sort by score (closest to English IC first)
+    candidates.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap());
+
+    // Add overview as first candidate
+    candidates.insert(0, Candidate {
+        name: "Overview".to_string(),
+        score: 0.0,
+        text: overview_text,
+    });
+
+    // Keep only top 4 candidates after overview
+    if candidates.len() > 5 {
+        candidates.truncate(5);
+    }
+
+    // Combined IC, Kasiski and Friedman ranking after the overview
+    let mut ranking_text = format!("Friedman estimate: {:.2}\n\n", friedman_estimate(text));
+    for estimate in rank_periods(text, max_period) {
+        ranking_text.push_str(&format!("p={}: score {:.3} | IC {:.3} | Kasiski {:.0}%\n",
+            estimate.period, estimate.score, estimate.average_ic, estimate.kasiski * 100.0));
+    }
+    candidates.insert(1, Candidate {
+        name: "Period Ranking".to_string(),
+        score: 0.0,
+        text: ranking_text,
+    });
+
+    // Kasiski examination as an independent check on the IC
+    let report = kasiski_examination(text, max_period);
+    let mut kasiski_text = String::from("Factor tally (factor: spacings divisible):\n");
+    for (factor, count) in &report.factor_counts {
+        kasiski_text.push_str(&format!("{}: {}\n", factor, count));
+    }
+    kasiski_text.push_str(&format!("\n{} repeated sequences:\n", report.repeats.len()));
+    for repeat in &report.repeats {
+        kasiski_text.push_str(&format!("{} at {:?} | spacings {:?}\n",
+            repeat.sequence, repeat.positions, repeat.spacings));
+    }
+    candidates.insert(2, Candidate {
+        name: "Kasiski".to_string(),
+        score: 0.0,
+        text: kasiski_text,
+    });
+
+    candidates
+}
+
 impl eframe::App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Check for results from the background thread
         if let Some(receiver) = &self.result_receiver {
             match receiver.try_recv() {
-                Ok(candidates) => {
+                Ok((title, candidates, solution)) => {
+                    if let Some((text, key)) = solution {
+                        self.workbench.set_solution(text, key);
+                    }
                     self.candidates = candidates;
                     self.show_result = true;
+                    self.result_title = title;
                     self.decryption_in_progress = false;
                     self.result_receiver = None;
                     self.selected_tab = 0;
@@ -254,7 +389,11 @@ impl eframe::App for MyApp {
 
         // Show results window when needed
         if self.show_result {
-            self.show_candidates_dialog(ctx, "Decryption Results");
+            self.show_candidates_dialog(ctx, self.result_title);
+        }
+
+        if self.workbench.open {
+            self.workbench.show(ctx, &self.my_string);
         }
 
         egui::CentralPanel::default().show(ctx, |ui| {
@@ -279,29 +418,34 @@ impl eframe::App for MyApp {
 
                 // Settings section
                 ui.group(|ui| {
-                    ui.label("Decryption Settings:");
+                    ui.label("Cipher Settings:");
+                    ui.add_space(8.0);
+
+                    ui.horizontal(|ui| {
+                        ui.label("Mode:");
+                        ui.add_space(8.0);
+                        ui.radio_value(&mut self.mode, Mode::Solve, "Solve");
+                        ui.radio_value(&mut self.mode, Mode::Encrypt, "Encrypt");
+                        ui.radio_value(&mut self.mode, Mode::Decrypt, "Decrypt with Key");
+                    });
                     ui.add_space(8.0);
 
+                    let settings = self.cipher_type.cipher().settings(&self.decrypter(None).params);
+
                     // Method selection with better layout
                     ui.horizontal(|ui| {
                         ui.label("Method:");
                         ui.add_space(8.0);
                         egui::ComboBox::from_label("")
-                            .selected_text(match self.cipher_type {
-                                CipherType::Columnar => "Columnar Transposition",
-                                CipherType::Periodic => "Periodic Transposition",
-                                CipherType::Vigenere => "Vigenère Cipher",
-                                CipherType::Beaufort => "Beaufort Cipher",
-                            })
+                            .selected_text(self.cipher_type.name())
                             .show_ui(ui, |ui| {
-                                ui.selectable_value(&mut self.cipher_type, CipherType::Columnar, "Columnar Transposition");
-                                ui.selectable_value(&mut self.cipher_type, CipherType::Periodic, "Periodic Transposition");
-                                ui.selectable_value(&mut self.cipher_type, CipherType::Vigenere, "Vigenère Cipher");
-                                ui.selectable_value(&mut self.cipher_type, CipherType::Beaufort, "Beaufort Cipher");
+                                for cipher in CIPHERS {
+                                    ui.selectable_value(&mut self.cipher_type, cipher.cipher_type(), cipher.name());
+                                }
                             });
 
-                        // Transpose checkbox (only for transposition ciphers)
-                        if matches!(self.cipher_type, CipherType::Columnar | CipherType::Periodic) {
+                        // Transpose checkbox, for the ciphers whose key it belongs to
+                        if settings.contains(&Setting::Transpose) {
                             ui.add_space(16.0);
                             ui.checkbox(&mut self.transpose, "Transpose");
                         }
@@ -309,51 +453,36 @@ impl eframe::App for MyApp {
                     ui.add_space(8.0);
 
                     // Settings based on method
-                    match self.cipher_type {
-                        CipherType::Columnar => {
-                            ui.horizontal(|ui| {
-                                ui.label("Max Key Length:");
-                                ui.add_space(8.0);
-                                ui.add(egui::TextEdit::singleline(&mut self.max_key_length)
-                                    .desired_width(60.0));
-                            });
-                        },
-                        CipherType::Periodic => {
-                            ui.vertical(|ui| {
-                                ui.horizontal(|ui| {
-                                    ui.label("Period:");
-                                    ui.add_space(8.0);
-                                    ui.add(egui::TextEdit::singleline(&mut self.period)
-                                        .desired_width(60.0));
-                                    ui.add_space(16.0);
-                                    ui.checkbox(&mut self.check_all_periods, "Check all periods up to");
-                                });
-                                if self.check_all_periods {
-                                    ui.horizontal(|ui| {
-                                        ui.label("Max Period:");
-                                        ui.add_space(8.0);
-                                        ui.add(egui::TextEdit::singleline(&mut self.max_key_length)
-                                            .desired_width(60.0));
-                                    });
-                                }
-                            });
-                        }
-                        CipherType::Vigenere => {
-                            ui.horizontal(|ui| {
-                                ui.label("Period:");
-                                ui.add_space(8.0);
-                                ui.add(egui::TextEdit::singleline(&mut self.period)
-                                    .desired_width(60.0));
-                            });
-                        },
-                        CipherType::Beaufort => {
-                            ui.horizontal(|ui| {
-                                ui.label("Period:");
-                                ui.add_space(8.0);
-                                ui.add(egui::TextEdit::singleline(&mut self.period)
-                                    .desired_width(60.0));
-                            });
+                    if self.mode != Mode::Solve {
+                        ui.horizontal(|ui| {
+                            ui.label("Key:");
+                            ui.add_space(8.0);
+                            ui.add(egui::TextEdit::singleline(&mut self.key)
+                                .desired_width(200.0));
+                        });
+                    } else {
+                        // Each cipher lists the controls its attack reads
+                        for setting in settings {
+                            if setting != Setting::Transpose {
+                                self.setting_ui(ui, setting);
+                            }
                         }
+
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            ui.label("Scoring:");
+                            ui.add_space(8.0);
+                            egui::ComboBox::from_id_salt("scoring")
+                                .selected_text(self.scorer.name())
+                                .show_ui(ui, |ui| {
+                                    for scorer in scorers() {
+                                        let selected = scorer.id() == self.scorer.id();
+                                        if ui.selectable_label(selected, scorer.name()).clicked() {
+                                            self.scorer = scorer;
+                                        }
+                                    }
+                                });
+                        });
                     }
 
                     // Show factors if available
@@ -401,49 +530,51 @@ impl eframe::App for MyApp {
                         self.factors = Some(compute_factors(self.my_string.len()));
                     }
 
-                    if ui.button("📊 Find IC").clicked() {
+                    let ic_button = ui.add_enabled(!self.decryption_in_progress, egui::Button::new("📊 Find IC"));
+                    if ic_button.clicked() {
                         let text = self.my_string.clone();
                         let max_period = self.max_ic_period as usize;
-                        let mut candidates = Vec::new();
-                        
-                        // Create overview tab first
-                        let mut overview_text = String::new();
-                        for period in 1..=max_period {
-                            let output = Decrypter::index_of_coincidence(&text, period);
-                            let avg = output.iter().sum::<f32>() / output.len() as f32;
-                            let score = (avg - 0.066).abs(); // Closer to English IC is better
-                            
-                            overview_text.push_str(&format!("p={}: {:.3} | [{}]\n", period, (avg - 0.066).abs(),
-                                output.iter().map(|&x| format!("{:.3}", x)).collect::<Vec<String>>().join(", ")));
-                            
+                        let ctx_clone = ctx.clone();
+
+                        // The Kasiski examination can take a while on long texts
+                        let (sender, receiver) = mpsc::channel();
+                        self.result_receiver = Some(receiver);
+                        self.decryption_in_progress = true;
+                        std::thread::spawn(move || {
+                            let _ = sender.send(("IC Results", ic_results(&text, max_period), None));
+                            ctx_clone.request_repaint();
+                        });
+                    }
+
+                    if ui.button("🔍 Identify").clicked() {
+                        let identification = identify_cipher(&self.my_string);
+                        let mut candidates = vec![Candidate {
+                            name: "Statistics".to_string(),
+                            score: 0.0,
+                            text: identification.stats.to_string(),
+                        }];
+                        for (family, confidence) in identification.families {
                             candidates.push(Candidate {
-                                name: format!("p={}", period),
-                                score,
-                                text: format!("IC: {:.3}\nValues: [{}]", avg, 
-                                    output.iter().map(|&x| format!("{:.3}", x)).collect::<Vec<String>>().join(", ")),
+                                name: format!("{} ({:.0}%)", family.name(), confidence * 100.0),
+                                score: confidence,
+                                text: format!("Confidence: {:.0}%\nCiphers: {}", confidence * 100.0, family.examples()),
                             });
                         }
-                        
-                        // Sort by score (closest to English IC first)
-                        candidates.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap());
-                        
-                        // Add overview as first candidate
-                        candidates.insert(0, Candidate {
-                            name: "Overview".to_string(),
-                            score: 0.0,
-                            text: overview_text,
-                        });
-                        
-                        // Keep only top 4 candidates after overview
-                        if candidates.len() > 5 {
-                            candidates.truncate(5);
-                        }
-                        
+
                         self.candidates = candidates;
                         self.show_result = true;
+                        self.result_title = "Cipher Identification";
                         self.selected_tab = 0;
                     }
 
+                    if self.mode != Mode::Solve {
+                        let label = if self.mode == Mode::Encrypt { "🔒 Encrypt" } else { "🔓 Decrypt" };
+                        if ui.button(label).clicked() {
+                            self.run_with_key();
+                        }
+                        return;
+                    }
+
                     let decrypt_button = ui.add_enabled(
                         !self.decryption_in_progress,
                         egui::Button::new(
@@ -454,11 +585,7 @@ impl eframe::App for MyApp {
                     if decrypt_button.clicked() {
                         let text_to_decrypt = self.my_string.clone();
                         let ctx_clone = ctx.clone();
-                        let transpose = self.transpose;
-                        let cipher_type = self.cipher_type;
-                        let max_key = self.max_key_length.parse::<usize>().unwrap_or(8);
-                        let period = self.period.parse::<usize>().unwrap_or(3);
-                        let check_all_periods = self.check_all_periods;
+                        let decrypter = self.decrypter(None);
 
                         // Create a channel for results
                         let (sender, receiver) = mpsc::channel();
@@ -467,16 +594,17 @@ impl eframe::App for MyApp {
 
                         // Start decryption in a separate thread
                         std::thread::spawn(move || {
-                            let decrypter = Decrypter {
-                                cipher_type,
-                                key: None,
-                                max_key_length: max_key,
-                                period,
-                                check_all_periods,
+                            // The workbench is seeded from the cipher alphabet of the best candidate,
+                            // for ciphers that report one
+                            let cipher = decrypter.cipher_type.cipher();
+                            let (result, solution) = match cipher.attack_with_alphabets(&text_to_decrypt, &decrypter.params) {
+                                Some(keyed) => {
+                                    let solution = keyed.first().map(|&(_, key)| (text_to_decrypt.clone(), key));
+                                    (keyed.into_iter().map(|(candidate, _)| candidate).collect(), solution)
+                                }
+                                None => (decrypter.decrypt(&text_to_decrypt), None),
                             };
-
-                            let result = decrypter.decrypt_with_transpose(&text_to_decrypt, transpose);
-                            let _ = sender.send(result);
+                            let _ = sender.send(("Decryption Results", result, solution));
                             ctx_clone.request_repaint();
                         });
                     }
@@ -491,566 +619,3 @@ impl eframe::App for MyApp {
     }
 }
 
-struct Decrypter {
-    cipher_type: CipherType,
-    key: Option<String>,
-    max_key_length: usize,
-    period: usize,
-    check_all_periods: bool,
-}
-
-fn compute_factors(n: usize) -> Vec<usize> {
-    let mut result: Vec<usize> = Vec::new();
-    result.push(1);
-    if n > 1 {
-        result.push(n);
-    }
-    let int_sqrt = (n as f32).sqrt() as usize;
-    for i in 2..=int_sqrt {
-        if n % i == 0 {
-            result.push(i);
-            if i != n / i {
-                result.push(n / i);
-            }
-        }
-    }
-
-    // Heuristic: sort the factors from smallest to biggest
-    result.sort_by(|a, b| a.cmp(b));
-    result
-}
-
-impl Decrypter {
-    fn decrypt(&self, text: &str) -> Vec<Candidate> {
-        match self.cipher_type {
-            CipherType::Columnar => self.decrypt_columnar(text),
-            CipherType::Periodic => self.decrypt_periodic(text),
-            CipherType::Vigenere => self.decrypt_vigenere(text),
-            CipherType::Beaufort => self.decrypt_beaufort(text),
-        }
-    }
-
-    fn decrypt_columnar(&self, text: &str) -> Vec<Candidate> {
-        // Create a mutex-protected heap to collect results from different threads
-        let heap = Mutex::new(BinaryHeap::new());
-
-        // Find the factors of the text length
-        let factors = compute_factors(text.len());
-        println!("Factors: {:?}", factors);
-
-        // Use the specified max key length instead of a constant
-        let max_key_length = self.max_key_length;
-
-        // Process key lengths in parallel
-        (1..=max_key_length).into_par_iter().for_each(|key_length| {
-            let permutations = (0..key_length).permutations(key_length);
-
-            // For each permutation in this key length
-            for permutation in permutations {
-                let decrypted_text = self.columnar_inv(text, &permutation, false);
-                let score = Self::english_score(&decrypted_text);
-
-                // Update the heap with this candidate
-                let mut heap_guard = heap.lock().unwrap();
-                heap_guard.push(Reverse((score, decrypted_text, permutation)));
-
-                if heap_guard.len() > 3 {  // Keep only top 3 candidates
-                    heap_guard.pop();
-                }
-            }
-        });
-
-        // Extract results
-        let heap_contents = heap.lock().unwrap();
-        let mut best: Vec<_> = heap_contents.iter().cloned().collect();
-        best.sort_by(|a, b| a.cmp(b)); // Sort in ascending order (highest scores first)
-        drop(heap_contents); // Release the lock
-
-        // Convert to candidates
-        best.iter().enumerate().map(|(i, Reverse((score, text, key)))| {
-            Candidate {
-                name: format!("Candidate {}", i + 1),
-                score: *score as f32,
-                text: format!("Key: {:?}\nText: {}", key, text),
-            }
-        }).collect()
-    }
-
-    fn decrypt_periodic(&self, text: &str) -> Vec<Candidate> {
-        // Create a mutex-protected heap to collect results from different threads
-        let heap = Mutex::new(BinaryHeap::new());
-        let period = self.period;
-        let max_period = if self.check_all_periods { self.max_key_length } else { period };
-        let periods_to_check = if self.check_all_periods {
-            (period..=max_period).collect::<Vec<usize>>()
-        } else {
-            vec![period]
-        };
-
-        // Process each period in parallel
-        periods_to_check.into_par_iter().for_each(|current_period| {
-            let permutations: Vec<Vec<usize>> = (0..current_period).permutations(current_period).collect();
-
-            // Process permutations for this period
-            for permutation in permutations {
-                let decrypted_text = self.periodic_inv(text, &permutation);
-                let score = Self::english_score(&decrypted_text);
-
-                // Update the heap with this candidate
-                let mut heap_guard = heap.lock().unwrap();
-                heap_guard.push(Reverse((score, decrypted_text, permutation)));
-
-                if heap_guard.len() > 3 {  // Keep only top 3 candidates
-                    heap_guard.pop();
-                }
-            }
-        });
-
-        // Extract results
-        let heap_contents = heap.lock().unwrap();
-        let mut best: Vec<_> = heap_contents.iter().cloned().collect();
-        best.sort_by(|a, b| a.cmp(b)); // Sort in ascending order (highest scores first)
-        drop(heap_contents); // Release the lock
-
-        // Convert to candidates
-        best.iter().enumerate().map(|(i, Reverse((score, text, key)))| {
-            Candidate {
-                name: format!("Candidate {}", i + 1),
-                score: *score as f32,
-                text: format!("Key: {:?}\nText: {}", key, text),
-            }
-        }).collect()
-    }
-
-    fn decrypt_vigenere(&self, text: &str) -> Vec<Candidate> {
-        let period = self.period;
-        println!("Starting Vigenère decryption with period {}", period);
-        
-        // Split text into period components
-        let mut char_groups: Vec<Vec<char>> = vec![Vec::new(); period];
-        for (i, c) in text.chars().enumerate() {
-            char_groups[i % period].push(c);
-        }
-
-        // For each position in the key, find the most likely shifts
-        let mut key_positions: Vec<Vec<usize>> = Vec::new();
-        
-        // For each position in the key
-        for (i, group) in char_groups.iter().enumerate() {
-            println!("Analyzing position {} ({} characters)", i, group.len());
-            
-            // Count frequencies in this group
-            let mut freq_table = vec![0; 26];
-            for c in group {
-                if c.is_ascii_alphabetic() {
-                    freq_table[c.to_ascii_lowercase() as usize - 'a' as usize] += 1;
-                }
-            }
-
-            // Find top 3 most common letters in this group
-            let mut freq_positions: Vec<(usize, usize)> = freq_table.iter()
-                .enumerate()
-                .map(|(pos, &count)| (pos, count))
-                .collect();
-            
-            freq_positions.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
-            let top_3_positions: Vec<usize> = freq_positions.iter()
-                .take(3)
-                .map(|&(pos, _)| pos)
-                .collect();
-
-            println!("Top 3 letters at position {}: {:?}", i, 
-                top_3_positions.iter().map(|&p| ((p as u8 + b'a') as char)).collect::<Vec<char>>());
-
-            // For each of the top 3 positions, calculate the shift assuming it maps to 'E'
-            let mut shifts = Vec::new();
-            for &pos in &top_3_positions {
-                let shift = (pos + 22) % 26; // 22 = (26 - 4) mod 26
-                shifts.push(shift);
-            }
-            key_positions.push(shifts);
-        }
-
-        // Generate all possible combinations of shifts
-        let mut key_candidates: Vec<Vec<usize>> = Vec::new();
-        for shifts in key_positions {
-            if key_candidates.is_empty() {
-                for &shift in &shifts {
-                    key_candidates.push(vec![shift]);
-                }
-            } else {
-                let mut new_candidates = Vec::new();
-                for mut key in key_candidates {
-                    for &shift in &shifts {
-                        let mut new_key = key.clone();
-                        new_key.push(shift);
-                        new_candidates.push(new_key);
-                    }
-                }
-                key_candidates = new_candidates;
-            }
-        }
-
-        println!("Generated {} key candidates", key_candidates.len());
-
-        // Try each key candidate and score the results
-        let mut scored_results: Vec<(String, String, f32)> = Vec::new();
-        for (i, key) in key_candidates.iter().enumerate() {
-            if i % 100 == 0 {
-                println!("Testing key candidate {}/{}", i, key_candidates.len());
-            }
-            
-            if key.len() == period {
-                let mut result = String::new();
-                for (i, c) in text.chars().enumerate() {
-                    let shift = key[i % period];
-                    let base = if c.is_uppercase() { 'A' } else { 'a' } as u8;
-                    let decrypted = ((c as u8 - base + 26 - shift as u8) % 26 + base) as char;
-                    result.push(decrypted);
-                }
-
-                let score = Self::english_score(&result) as f32;
-                let key_str: String = key.iter()
-                    .map(|&shift| ((shift as u8 + b'a') as char))
-                    .collect();
-                
-                scored_results.push((key_str, result, score));
-            }
-        }
-
-        println!("Found {} valid results", scored_results.len());
-
-        // Sort by score and take top 5
-        scored_results.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
-        scored_results.truncate(5);
-
-        // Convert to candidates
-        scored_results.iter().enumerate().map(|(i, (key, text, score))| {
-            Candidate {
-                name: format!("Candidate {}", i + 1),
-                score: *score,
-                text: format!("Key: {}\nDecryption:\n{}", key, text),
-            }
-        }).collect()
-    }
-
-    fn decrypt_beaufort(&self, text: &str) -> Vec<Candidate> {
-        let period = self.period;
-        println!("Starting Beaufort decryption with period {}", period);
-        
-        // Split text into period components
-        let mut char_groups: Vec<Vec<char>> = vec![Vec::new(); period];
-        for (i, c) in text.chars().enumerate() {
-            char_groups[i % period].push(c);
-        }
-
-        // For each position in the key, find the most likely shifts
-        let mut key_positions: Vec<Vec<usize>> = Vec::new();
-        
-        // For each position in the key
-        for (i, group) in char_groups.iter().enumerate() {
-            
-            // Count frequencies in this group
-            let mut freq_table = vec![0; 26];
-            for c in group {
-                if c.is_ascii_alphabetic() {
-                    freq_table[c.to_ascii_lowercase() as usize - 'a' as usize] += 1;
-                }
-            }
-
-            // Find top N most common letters in this group
-            let mut freq_positions: Vec<(usize, usize)> = freq_table.iter()
-                .enumerate()
-                .map(|(pos, &count)| (pos, count))
-                .collect();
-            
-            freq_positions.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
-            let top_positions: Vec<usize> = freq_positions.iter()
-                .take(BEAUFORT_TOP_LETTERS)
-                .map(|&(pos, _)| pos)
-                .collect();
-
-            // For each top letter, calculate what letter in the key would transform to 'E' in Beaufort
-            println!("Likely key letters at position {}: {:?}", i,
-                top_positions.iter().map(|&pos| {
-                    let cipher_letter = ((pos as u8 + b'a') as char);
-                    Self::beaufort_key_letter(cipher_letter)
-                }).collect::<Vec<char>>());
-
-            // For each of the top positions, calculate the shift assuming it maps to 'E'
-            let mut shifts = Vec::new();
-            for &pos in &top_positions {
-                let shift = (pos + 4) % 26;
-                shifts.push(shift);
-            }
-            key_positions.push(shifts);
-        }
-
-        // Generate all possible combinations of shifts
-        let mut key_candidates: Vec<Vec<usize>> = Vec::new();
-        for shifts in key_positions {
-            if key_candidates.is_empty() {
-                for &shift in &shifts {
-                    key_candidates.push(vec![shift]);
-                }
-            } else {
-                let mut new_candidates = Vec::new();
-                for mut key in key_candidates {
-                    for &shift in &shifts {
-                        let mut new_key = key.clone();
-                        new_key.push(shift);
-                        new_candidates.push(new_key);
-                    }
-                }
-                key_candidates = new_candidates;
-            }
-        }
-
-        println!("Generated {} key candidates", key_candidates.len());
-
-        // Try each key candidate and score the results
-        let mut scored_results: Vec<(String, String, f32)> = Vec::new();
-        for (i, key) in key_candidates.iter().enumerate() {
-            if i % 100 == 0 {
-                println!("Testing key candidate {}/{}", i, key_candidates.len());
-            }
-            
-            if key.len() == period {
-                let mut result = String::new();
-                for (i, c) in text.chars().enumerate() {
-                    let shift = key[i % period];
-                    let base = if c.is_uppercase() { 'A' } else { 'a' } as u8;
-                    let decrypted = ((shift as u8 + 26 - (c as u8 - base)) % 26 + base) as char;
-                    result.push(decrypted);
-                }
-
-                let score = Self::english_score(&result) as f32;
-                let key_str: String = key.iter()
-                    .map(|&shift| ((shift as u8 + b'a') as char))
-                    .collect();
-                
-                scored_results.push((key_str, result, score));
-            }
-        }
-
-        println!("Found {} valid results", scored_results.len());
-
-        // Sort by score and take top 5
-        scored_results.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
-        scored_results.truncate(5);
-
-        // Convert to candidates
-        scored_results.iter().enumerate().map(|(i, (key, text, score))| {
-            Candidate {
-                name: format!("Candidate {}", i + 1),
-                score: *score,
-                text: format!("Key: {}\nDecryption:\n{}", key, text),
-            }
-        }).collect()
-    }
-
-    fn decrypt_with_transpose(&self, text: &str, transpose: bool) -> Vec<Candidate> {
-        match self.cipher_type {
-            CipherType::Columnar => self.decrypt_columnar_with_transpose(text, transpose),
-            CipherType::Periodic => self.decrypt_periodic(text),
-            CipherType::Vigenere => self.decrypt_vigenere(text),
-            CipherType::Beaufort => self.decrypt_beaufort(text),
-        }
-    }
-
-    fn decrypt_columnar_with_transpose(&self, text: &str, transpose: bool) -> Vec<Candidate> {
-        // Create a mutex-protected heap to collect results from different threads
-        let heap = Mutex::new(BinaryHeap::new());
-
-        // Find the factors of the text length
-        let factors = compute_factors(text.len());
-        println!("Factors: {:?}", factors);
-
-        // Use the specified max key length instead of a constant
-        let max_key_length = self.max_key_length;
-
-        // Process key lengths in parallel
-        (1..=max_key_length).into_par_iter().for_each(|key_length| {
-            let permutations = (0..key_length).permutations(key_length);
-
-            // For each permutation in this key length
-            for permutation in permutations {
-                let decrypted_text = self.columnar_inv(text, &permutation, transpose);
-                let score = Self::english_score(&decrypted_text);
-
-                // Update the heap with this candidate
-                let mut heap_guard = heap.lock().unwrap();
-                heap_guard.push(Reverse((score, decrypted_text, permutation)));
-
-                if heap_guard.len() > 3 {  // Keep only top 3 candidates
-                    heap_guard.pop();
-                }
-            }
-        });
-
-        // Extract results
-        let heap_contents = heap.lock().unwrap();
-        let mut best: Vec<_> = heap_contents.iter().cloned().collect();
-        best.sort_by(|a, b| a.cmp(b)); // Sort in ascending order (highest scores first)
-        drop(heap_contents); // Release the lock
-
-        // Convert to candidates
-        best.iter().enumerate().map(|(i, Reverse((score, text, key)))| {
-            Candidate {
-                name: format!("Candidate {}", i + 1),
-                score: *score as f32,
-                text: format!("Key: {:?}\nText: {}", key, text),
-            }
-        }).collect()
-    }
-
-    fn periodic_inv(&self, text: &str, key: &Vec<usize>) -> String {
-        let chars: Vec<char> = text.chars().collect();
-        let mut result = Vec::new();
-        let period = key.len();
-
-        // Process the text in chunks of size period
-        for chunk in chars.chunks(period) {
-            if chunk.len() == period {
-                // Only apply permutation to complete chunks
-                let mut chunk_vec = chunk.to_vec();
-                // Apply the inverse permutation
-                for (i, &pos) in key.iter().enumerate() {
-                    chunk_vec[pos] = chunk[i];
-                }
-                result.extend(chunk_vec);
-            } else {
-                // For partial chunks at the end, just add them as is
-                result.extend(chunk);
-            }
-        }
-
-        result.into_iter().collect()
-    }
-
-    fn columnar_inv(&self, text: &str, key: &Vec<usize>, transpose: bool) -> String {
-        // will be = key[(n mod (key.len))]
-        let n = text.len();
-        // text length
-        let k_l = key.len();
-        // |k|, key length
-        let s_l = n / k_l;
-        // |s|, number of letters in each column, rounded down, i.e. num rows
-        let r = n % k_l;
-        // this is the length of the last row, also num columns with an extra row
-
-        let mut output = vec!['\0'; n];
-        let chars: Vec<char> = text.chars().collect();
-
-        // get the inverse key
-        let mut key_inv = vec![0; k_l];
-        for (i, &k) in key.iter().enumerate() {
-            key_inv[k as usize] = i;
-        }
-
-        let mut offset = 0;
-        for col in key_inv {
-            let row_len = if col < r {s_l + 1} else {s_l};
-            for row in 0..row_len {
-                let c_index = offset + row;
-
-                if transpose {
-                    output[col * s_l + row + (if col < r { col } else {r})] = chars[c_index];
-                } else {
-                    output[row * k_l + col] = chars[c_index];
-                }
-            }
-            offset += row_len;
-        }
-
-        output.into_iter().collect()
-    }
-
-    fn english_score(text: &str) -> usize {
-        let text = text.to_lowercase();
-        let mut score = 0;
-
-        // Score based on trigrams
-        for (trigram, weight) in COMMON_TRIGRAMS {
-            let count = text.matches(trigram).count();
-            score += count * weight;
-        }
-
-        // Score based on bigrams
-        for (bigram, weight) in COMMON_BIGRAMS {
-            let count = text.matches(bigram).count();
-            score += count * weight;
-        }
-
-        // Score based on common words
-        for (word, weight) in COMMON_WORDS {
-            let count = text.matches(word).count();
-            score += count * weight;
-        }
-
-        // Score based on character frequencies
-        let text_chars: Vec<char> = text.chars().collect();
-        for (c, weight) in CHAR_FREQUENCIES {
-            let count = text_chars.iter().filter(|&&x| x == c).count();
-            score += count * weight;
-        }
-
-        score
-    }
-
-    fn print_index_of_coincidence_table(text: &str) {
-        for period in 1..=10 {
-            let output = Self::index_of_coincidence(text, period);
-            let avg = output.iter().sum::<f32>() / output.len() as f32;
-            println!("p={}: {:?} | {:?}", period, (avg - 0.066).abs(), output);
-        }
-    }
-
-    fn index_of_coincidence(text: &str, period: usize) -> Vec<f32> {
-        // 1. split text into d period components
-        // 0 1 2 3 4 5 6 7 8 9 10
-        // 0 1 2 0 1 2 0 1 2 0 1
-
-        let mut char_groups: Vec<Vec<char>> = vec![Vec::new(); period];
-        let mut output: Vec<f32> = vec![0.0; period];
-
-        for (i, char) in text.chars().enumerate() {
-            char_groups[i % period].push(char);
-        }
-
-        for (i, char_group) in char_groups.into_iter().enumerate() {
-            let N = char_group.len();
-            // 2. get frequency table for the char_group
-            let mut freq_table: Vec<f32> = vec![0.0; 26];
-            for char in char_group {
-                freq_table[char as usize - 'a' as usize] += 1.0;
-            }
-
-            //3.
-            // output[i % period] +=
-            //     Decrypter::get_frequency(char) * (Decrypter::get_frequency(char) - 1.0) /
-            //     (period as f32 * (period as f32 - 1.));
-            for j in 0..26 {
-                output[i % period] += freq_table[j] * (freq_table[j] - 1.0) / (N as f32 * (N as f32 - 1.));
-            }
-        }
-
-        //println!("Char groups: {:?}", output);
-
-        output
-    }
-
-    /// Helper function to calculate what letter in the key would transform a given ciphertext letter to 'E' in Beaufort cipher
-    /// Returns the key letter that would transform ciphertext_letter to 'E'
-    /// Using the formula from the working decryption: decrypted = (shift + 26 - (ciphertext - base)) % 26 + base
-    fn beaufort_key_letter(ciphertext_letter: char) -> char {
-        let base = if ciphertext_letter.is_uppercase() { 'A' } else { 'a' } as u8;
-        let cipher_pos = (ciphertext_letter as u8 - base) as usize;
-        let e_pos = 4; // 'E' is position 4 (0-based)
-        
-        // We want: e_pos = (key_pos + 26 - cipher_pos) % 26
-        // Therefore: key_pos = (e_pos + cipher_pos) % 26
-        let key_pos = (e_pos + cipher_pos) % 26;
-        ((key_pos as u8 + b'a') as char)
-    }
-}
\ No newline at end of file
@@ -0,0 +1,323 @@
+use crate::Decrypter;
+
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock};
+
+// English quadgram counts, one `ABCD count` pair per line (see data/README.md for the source)
+const QUADGRAM_DATA: &str = include_str!("../data/english_quadgrams.txt");
+
+// Common English words, one per line
+const WORD_DATA: &str = include_str!("../data/english_words.txt");
+
+// Relative frequency of each letter a-z in English text
+pub const ENGLISH_LETTER_FREQUENCIES: [f32; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966,
+    0.00153, 0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987,
+    0.06327, 0.09056, 0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
+// Index of coincidence of typical English text
+pub const ENGLISH_IC: f32 = 0.066;
+
+/// A fitness function used to rank candidate decryptions. Higher scores are always better.
+pub trait Scorer: Send + Sync {
+    /// Short lowercase identifier, as accepted on the command line.
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn score(&self, text: &str) -> f32;
+}
+
+/// Every built-in scorer, in the order they are offered to the user.
+pub fn scorers() -> Vec<Arc<dyn Scorer>> {
+    vec![
+        Arc::new(HeuristicScorer),
+        Arc::new(ChiSquaredScorer),
+        Arc::new(QuadgramScorer),
+        Arc::new(WordCoverageScorer),
+        Arc::new(IcDistanceScorer),
+    ]
+}
+
+pub fn scorer_from_id(id: &str) -> Option<Arc<dyn Scorer>> {
+    scorers().into_iter().find(|scorer| scorer.id() == id)
+}
+
+/// `Decrypter::english_score`: weighted counts of common n-grams, words and letters.
+pub struct HeuristicScorer;
+
+impl Scorer for HeuristicScorer {
+    fn id(&self) -> &'static str {
+        "heuristic"
+    }
+
+    fn name(&self) -> &'static str {
+        "Heuristic"
+    }
+
+    fn score(&self, text: &str) -> f32 {
+        Decrypter::english_score(text) as f32
+    }
+}
+
+/// Negated chi-squared statistic of the letter counts against English monograms.
+pub struct ChiSquaredScorer;
+
+impl Scorer for ChiSquaredScorer {
+    fn id(&self) -> &'static str {
+        "chi2"
+    }
+
+    fn name(&self) -> &'static str {
+        "Chi-squared"
+    }
+
+    fn score(&self, text: &str) -> f32 {
+        -chi_squared(&letter_counts(text))
+    }
+}
+
+/// Mean log10 probability of each quadgram, comparable across text lengths.
+pub struct QuadgramScorer;
+
+impl Scorer for QuadgramScorer {
+    fn id(&self) -> &'static str {
+        "quadgram"
+    }
+
+    fn name(&self) -> &'static str {
+        "Quadgram"
+    }
+
+    fn score(&self, text: &str) -> f32 {
+        quadgram_score(text)
+    }
+}
+
+/// Fraction of the letters that can be covered by dictionary words.
+pub struct WordCoverageScorer;
+
+impl Scorer for WordCoverageScorer {
+    fn id(&self) -> &'static str {
+        "words"
+    }
+
+    fn name(&self) -> &'static str {
+        "Word Coverage"
+    }
+
+    fn score(&self, text: &str) -> f32 {
+        word_coverage(text)
+    }
+}
+
+/// Negated distance between the text's index of coincidence and English's.
+pub struct IcDistanceScorer;
+
+impl Scorer for IcDistanceScorer {
+    fn id(&self) -> &'static str {
+        "ic"
+    }
+
+    fn name(&self) -> &'static str {
+        "IC Distance"
+    }
+
+    fn score(&self, text: &str) -> f32 {
+        -(index_of_coincidence(&letter_counts(text)) - ENGLISH_IC).abs()
+    }
+}
+
+/// Number of occurrences of each letter a-z, ignoring case and anything that is not a letter.
+pub fn letter_counts(text: &str) -> [usize; 26] {
+    let mut counts = [0; 26];
+    for letter in letter_indices(text) {
+        counts[letter] += 1;
+    }
+    counts
+}
+
+/// Chi-squared statistic of letter counts against English; lower means more English-like.
+pub fn chi_squared(counts: &[usize; 26]) -> f32 {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    counts.iter().zip(ENGLISH_LETTER_FREQUENCIES).map(|(&count, frequency)| {
+        let expected = frequency * total as f32;
+        (count as f32 - expected).powi(2) / expected
+    }).sum()
+}
+
+/// Index of coincidence of a set of letter counts.
+pub fn index_of_coincidence(counts: &[usize; 26]) -> f32 {
+    let n: usize = counts.iter().sum();
+    if n < 2 {
+        return 0.0;
+    }
+    let pairs: usize = counts.iter().map(|&count| count * count.saturating_sub(1)).sum();
+    pairs as f32 / (n * (n - 1)) as f32
+}
+
+fn dictionary() -> &'static HashSet<&'static str> {
+    static WORDS: OnceLock<HashSet<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| english_words().collect())
+}
+
+/// Bundled list of common English words, all lowercase, in the order of the file.
+pub fn english_words() -> impl Iterator<Item = &'static str> {
+    WORD_DATA.lines().map(str::trim).filter(|word| !word.is_empty())
+}
+
+/// Fraction (0-1) of the letters that fall inside dictionary words, choosing the
+/// segmentation that covers the most letters.
+pub fn word_coverage(text: &str) -> f32 {
+    const MAX_WORD_LENGTH: usize = 15;
+
+    let words = dictionary();
+    let letters: String = letter_indices(text).iter().map(|&l| (l as u8 + b'a') as char).collect();
+    let n = letters.len();
+    if n == 0 {
+        return 0.0;
+    }
+
+    // best[i] = most letters of letters[..i] that can be covered
+    let mut best = vec![0usize; n + 1];
+    for end in 1..=n {
+        best[end] = best[end - 1];
+        for start in end.saturating_sub(MAX_WORD_LENGTH)..end {
+            if words.contains(&letters[start..end]) {
+                best[end] = best[end].max(best[start] + end - start);
+            }
+        }
+    }
+    best[n] as f32 / n as f32
+}
+
+struct Quadgrams {
+    /// Log10 probability of every quadgram, indexed by the four letters in base 26
+    table: Vec<f32>,
+    /// Log10 probability given to quadgrams that never appear in the data
+    floor: f32,
+    /// Log10 probability of every bigram, from the first two letters of each quadgram
+    bigrams: Vec<f32>,
+}
+
+fn quadgrams() -> &'static Quadgrams {
+    static QUADGRAMS: OnceLock<Quadgrams> = OnceLock::new();
+    QUADGRAMS.get_or_init(|| {
+        let counts: Vec<(usize, f64)> = QUADGRAM_DATA
+            .lines()
+            .filter_map(|line| {
+                let (quadgram, count) = line.split_once(' ')?;
+                let index = quadgram.bytes().try_fold(0, |acc, b| {
+                    b.is_ascii_uppercase().then(|| acc * 26 + (b - b'A') as usize)
+                })?;
+                Some((index, count.trim().parse().ok()?))
+            })
+            .collect();
+        let total: f64 = counts.iter().map(|&(_, count)| count).sum();
+
+        // Quadgrams that never appear in the corpus are given half a count rather than an
+        // impossibly low probability
+        let floor = (0.5 / total).log10() as f32;
+        let mut table = vec![floor; 26 * 26 * 26 * 26];
+        let mut bigram_counts = vec![0.01; 26 * 26];
+        for (index, count) in counts {
+            table[index] = (count / total).log10() as f32;
+            bigram_counts[index / (26 * 26)] += count;
+        }
+        let bigrams = bigram_counts.iter().map(|&count| (count / total).log10() as f32).collect();
+        Quadgrams { table, floor, bigrams }
+    })
+}
+
+/// Log10 probability of the bigram of letters `a` and `b` (both 0-25).
+pub(crate) fn bigram_log_prob(a: usize, b: usize) -> f32 {
+    quadgrams().bigrams[a * 26 + b]
+}
+
+/// Letters of `text` as 0-25, ignoring case and anything that is not a letter.
+pub(crate) fn letter_indices(text: &str) -> Vec<usize> {
+    text.bytes()
+        .filter(|b| b.is_ascii_alphabetic())
+        .map(|b| (b.to_ascii_lowercase() - b'a') as usize)
+        .collect()
+}
+
+/// Lowercase letters for alphabet positions (0-25).
+pub(crate) fn indices_to_text<T: Copy + Into<usize>>(letters: &[T]) -> String {
+    letters.iter().map(|&letter| (letter.into() as u8 + b'a') as char).collect()
+}
+
+/// Mean log10 probability per quadgram, so texts of different lengths can be compared.
+/// With the bundled table English prose scores between about -4.4 and -3.9, and random
+/// letters about -6.13, just above the -6.25 given to quadgrams that never appear in it.
+pub fn quadgram_score(text: &str) -> f32 {
+    quadgram_score_indices(&letter_indices(text))
+}
+
+/// `quadgram_score` of text already turned into alphabet positions, for attacks that
+/// rescore the same text many times.
+pub(crate) fn quadgram_score_indices(letters: &[usize]) -> f32 {
+    let Quadgrams { table, floor, .. } = quadgrams();
+    if letters.len() < 4 {
+        return *floor;
+    }
+
+    let total: f32 = letters
+        .windows(4)
+        .map(|w| table[((w[0] * 26 + w[1]) * 26 + w[2]) * 26 + w[3]])
+        .sum();
+    total / (letters.len() - 3) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::SAMPLE_TEXT;
+
+    use rand::Rng;
+
+    #[test]
+    fn quadgram_scores_match_the_documented_ranges() {
+        let english = quadgram_score(SAMPLE_TEXT);
+        assert!((-4.4..-3.9).contains(&english), "{}", english);
+
+        let mut rng = rand::rng();
+        let random: Vec<usize> = (0..2000).map(|_| rng.random_range(0..26)).collect();
+        let random = quadgram_score_indices(&random);
+        assert!((-6.25..-6.0).contains(&random), "{}", random);
+        assert!(quadgrams().floor < random);
+    }
+
+    #[test]
+    fn every_scorer_ranks_english_above_scrambled_text() {
+        use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+        let mut letters = letter_indices(SAMPLE_TEXT);
+        letters.shuffle(&mut StdRng::seed_from_u64(3260));
+        let shuffled = indices_to_text(&letters);
+        // Shifting each letter by its position flattens the letter counts as a long key would
+        let letters = letter_indices(SAMPLE_TEXT);
+        let flattened: Vec<usize> = letters.iter().enumerate().map(|(i, &l)| (l + i) % 26).collect();
+        let flattened = indices_to_text(&flattened);
+
+        for scorer in scorers() {
+            let english = scorer.score(SAMPLE_TEXT);
+            // A shuffle keeps the letter counts, so only scorers that read letter order see it
+            match scorer.id() {
+                "chi2" | "ic" => assert_eq!(english, scorer.score(&shuffled), "{}", scorer.id()),
+                _ => assert!(english > scorer.score(&shuffled), "{}", scorer.id()),
+            }
+            assert!(english > scorer.score(&flattened), "{}", scorer.id());
+        }
+    }
+
+    #[test]
+    fn word_coverage_counts_letters_inside_words() {
+        assert_eq!(word_coverage("The dog."), 1.0);
+        assert_eq!(word_coverage("the qzx dog"), 2.0 / 3.0);
+        assert_eq!(word_coverage("qzx"), 0.0);
+        assert_eq!(word_coverage("1, 2, 3"), 0.0);
+    }
+}
@@ -0,0 +1,160 @@
+use eframe::egui;
+use cipher_solver_comp3260::scoring::{letter_counts, ENGLISH_LETTER_FREQUENCIES};
+
+// Characters per line when showing the ciphertext above its partial decryption
+const LINE_WIDTH: usize = 60;
+
+/// Hand-editable substitution key, for finishing off a solution the solver got mostly right.
+pub struct Workbench {
+    pub open: bool,
+    /// Plaintext letter typed for each ciphertext letter, empty while unknown
+    mapping: Vec<String>,
+    /// Key of the solver's best candidate, with the ciphertext it was found for
+    solution: Option<(String, [u8; 26])>,
+}
+
+impl Default for Workbench {
+    fn default() -> Self {
+        Self {
+            open: false,
+            mapping: vec![String::new(); 26],
+            solution: None,
+        }
+    }
+}
+
+impl Workbench {
+    /// Remembers the key of the solver's best candidate for `ciphertext`.
+    pub fn set_solution(&mut self, ciphertext: String, key: [u8; 26]) {
+        self.solution = Some((ciphertext, key));
+    }
+
+    /// Opens the workbench, seeded from the best automatic solution if there is one for
+    /// this ciphertext.
+    pub fn open_seeded(&mut self, ciphertext: &str) {
+        self.seed(ciphertext);
+        self.open = true;
+    }
+
+    /// The solver's key, if it was found for this ciphertext.
+    fn solution_for(&self, ciphertext: &str) -> Option<[u8; 26]> {
+        self.solution.as_ref().filter(|(text, _)| text == ciphertext).map(|&(_, key)| key)
+    }
+
+    fn seed(&mut self, ciphertext: &str) {
+        if let Some(key) = self.solution_for(ciphertext) {
+            self.mapping = vec![String::new(); 26];
+            for (plain, &cipher) in key.iter().enumerate() {
+                self.mapping[cipher as usize] = ((plain as u8 + b'a') as char).to_string();
+            }
+        }
+    }
+
+    /// Plaintext letter for a ciphertext letter (0-25), if one has been set.
+    fn plain_letter(&self, cipher: usize) -> Option<char> {
+        self.mapping[cipher].chars().next().filter(|c| c.is_ascii_alphabetic())
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> String {
+        ciphertext.chars().map(|c| {
+            if !c.is_ascii_alphabetic() {
+                return c;
+            }
+            self.plain_letter((c.to_ascii_lowercase() as u8 - b'a') as usize)
+                .map_or('_', |p| p.to_ascii_lowercase())
+        }).collect()
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, ciphertext: &str) {
+        let mut open = self.open;
+        egui::Window::new("Substitution Workbench")
+            .open(&mut open)
+            .resizable(true)
+            .default_size([640.0, 480.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let seed = ui.add_enabled(self.solution_for(ciphertext).is_some(),
+                            egui::Button::new("🎲 Seed from Best Candidate"))
+                        .on_disabled_hover_text("Decrypt this text with the Substitution cipher first");
+                    if seed.clicked() {
+                        self.seed(ciphertext);
+                    }
+                    if ui.button("Clear").clicked() {
+                        self.mapping = vec![String::new(); 26];
+                    }
+                });
+                ui.add_space(8.0);
+
+                // One editable plaintext letter under each ciphertext letter
+                egui::Grid::new("workbench_mapping").spacing([4.0, 2.0]).show(ui, |ui| {
+                    for c in 0..26 {
+                        ui.monospace(((c as u8 + b'A') as char).to_string());
+                    }
+                    ui.end_row();
+                    for entry in self.mapping.iter_mut() {
+                        ui.add(egui::TextEdit::singleline(entry).desired_width(14.0).char_limit(1));
+                    }
+                    ui.end_row();
+                });
+
+                // A plaintext letter can only come from one ciphertext letter
+                let mut used = [0; 26];
+                for c in 0..26 {
+                    if let Some(p) = self.plain_letter(c) {
+                        used[(p.to_ascii_lowercase() as u8 - b'a') as usize] += 1;
+                    }
+                }
+                let clashes: String = (0..26).filter(|&p| used[p] > 1).map(|p| (p as u8 + b'a') as char).collect();
+                if !clashes.is_empty() {
+                    ui.colored_label(egui::Color32::RED, format!("Used more than once: {}", clashes));
+                }
+                ui.add_space(8.0);
+
+                ui.horizontal_top(|ui| {
+                    // Ciphertext lines with the partial decryption under each
+                    ui.group(|ui| {
+                        egui::ScrollArea::vertical().id_salt("workbench_text").max_height(300.0).show(ui, |ui| {
+                            let cipher_chars: Vec<char> = ciphertext.chars().collect();
+                            let plain_chars: Vec<char> = self.decrypt(ciphertext).chars().collect();
+                            for (cipher_line, plain_line) in cipher_chars.chunks(LINE_WIDTH).zip(plain_chars.chunks(LINE_WIDTH)) {
+                                ui.monospace(cipher_line.iter().collect::<String>().to_uppercase());
+                                ui.monospace(plain_line.iter().collect::<String>());
+                                ui.add_space(4.0);
+                            }
+                        });
+                    });
+
+                    // Ciphertext letter frequencies beside the expected English ones
+                    ui.group(|ui| {
+                        let counts = letter_counts(ciphertext);
+                        let total = counts.iter().sum::<usize>().max(1);
+                        let mut by_count: Vec<(usize, usize)> = counts.iter().copied().enumerate().collect();
+                        by_count.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+                        let mut english: Vec<(usize, f32)> = ENGLISH_LETTER_FREQUENCIES.iter().copied().enumerate().collect();
+                        english.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+                        egui::Grid::new("workbench_frequencies").striped(true).show(ui, |ui| {
+                            ui.label("Cipher");
+                            ui.label("%");
+                            ui.label("English");
+                            ui.label("%");
+                            ui.end_row();
+                            for (&(letter, count), &(english_letter, frequency)) in by_count.iter().zip(&english) {
+                                if count > 0 {
+                                    ui.monospace(format!("{} ({})", (letter as u8 + b'A') as char, count));
+                                    ui.monospace(format!("{:.1}", count as f32 * 100.0 / total as f32));
+                                } else {
+                                    ui.label("");
+                                    ui.label("");
+                                }
+                                ui.monospace(((english_letter as u8 + b'a') as char).to_string());
+                                ui.monospace(format!("{:.1}", frequency * 100.0));
+                                ui.end_row();
+                            }
+                        });
+                    });
+                });
+            });
+        self.open = open;
+    }
+}